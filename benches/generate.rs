@@ -0,0 +1,58 @@
+//! how long each generator takes to carve a Maze, and how `cut_up_maze`
+//! holds up as the grid grows
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use maze_climb::generator::{self, MazeRng};
+use maze_climb::maze::Maze;
+use rand::SeedableRng;
+
+/// side of the square grid every generator carves
+const GENERATOR_SIZE: usize = 100;
+
+/// sides of the square grids `cut_up_maze` carves
+const CUT_UP_SIZES: [usize; 3] = [500, 1000, 2000];
+
+fn generators(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generators");
+    // a square grid is plain, so caves can be carved on it too
+    for name in generator::NAMES.into_iter().chain(["cave"]) {
+        let gen = generator::from_name(name).unwrap();
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || {
+                    (
+                        Maze::new(GENERATOR_SIZE, GENERATOR_SIZE),
+                        MazeRng::seed_from_u64(7),
+                    )
+                },
+                |(mut maze, mut rng)| {
+                    gen.generate(&mut maze, &mut rng);
+                    maze
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn cut_up_maze(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cut_up_maze");
+    group.sample_size(10);
+    for size in CUT_UP_SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter_batched(
+                || (Maze::new(size, size), MazeRng::seed_from_u64(7)),
+                |(mut maze, mut rng)| {
+                    maze.cut_up_maze(usize::MAX, &mut rng);
+                    maze
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generators, cut_up_maze);
+criterion_main!(benches);
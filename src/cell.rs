@@ -1,19 +1,390 @@
+use std::fmt;
 use yew::prelude::*;
 use yew::Callback;
 
+use crate::position::{Axis, Direction, Position};
+use crate::topology::{ring_len, Topology};
+
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Wall {
     No,
     Yes,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+/// ground covering a cell, making it slower to cross
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Terrain {
+    #[default]
+    Floor,
+    Ice,
+    Mud,
+    Water,
+}
+
+impl Terrain {
+    /// the kinds of terrain that cost more than bare floor
+    pub const ROUGH: [Terrain; 3] = [Terrain::Ice, Terrain::Mud, Terrain::Water];
+
+    /// cost of stepping onto a cell covered in it
+    pub fn cost(self) -> usize {
+        match self {
+            Terrain::Floor => 1,
+            Terrain::Ice => 2,
+            Terrain::Mud => 3,
+            Terrain::Water => 5,
+        }
+    }
+
+    /// colour of a cell covered in it that isn't on the path
+    fn fill(self) -> &'static str {
+        match self {
+            Terrain::Floor => "grey",
+            Terrain::Ice => "lightcyan",
+            Terrain::Mud => "peru",
+            Terrain::Water => "steelblue",
+        }
+    }
+}
+
+/// flags kept in the low bits of a Cell
+const CLICKED: u8 = 1;
+const HINTED: u8 = 1 << 1;
+const EXPLORED: u8 = 1 << 2;
+const ACTIVE: u8 = 1 << 3;
+
+/// where the two bit fields of a Cell start, above its flags
+const UNDER_SHIFT: u8 = 4;
+const TERRAIN_SHIFT: u8 = 6;
+
+/// a cell of a Maze, packed into a single byte so that big Mazes stay
+/// small: four flags, then two bits for the axis of any passage under it
+/// and two for its terrain. Read and written through its methods
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "CellFields", into = "CellFields")
+)]
 pub struct Cell {
-    pub left: Wall,
-    pub right: Wall,
-    pub up: Wall,
-    pub down: Wall,
-    pub clicked: bool,
+    bits: u8,
+}
+
+impl Cell {
+    /// a cell with all of its walls up
+    pub fn closed() -> Self {
+        Cell { bits: ACTIVE }
+    }
+
+    pub fn clicked(&self) -> bool {
+        self.flag(CLICKED)
+    }
+
+    pub fn set_clicked(&mut self, clicked: bool) {
+        self.set_flag(CLICKED, clicked);
+    }
+
+    /// shown to the player as one of the next cells to click
+    pub fn hinted(&self) -> bool {
+        self.flag(HINTED)
+    }
+
+    pub fn set_hinted(&mut self, hinted: bool) {
+        self.set_flag(HINTED, hinted);
+    }
+
+    /// looked at by a solver on its way to the goal
+    pub fn explored(&self) -> bool {
+        self.flag(EXPLORED)
+    }
+
+    pub fn set_explored(&mut self, explored: bool) {
+        self.set_flag(EXPLORED, explored);
+    }
+
+    /// false for cells masked out of the Maze's shape
+    pub fn active(&self) -> bool {
+        self.flag(ACTIVE)
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.set_flag(ACTIVE, active);
+    }
+
+    /// axis of the passage tunnelling underneath, if the cell is a crossing
+    /// of a weave Maze. The cell's own corridor runs along the other axis
+    pub fn under(&self) -> Option<Axis> {
+        match self.field(UNDER_SHIFT) {
+            1 => Some(Axis::Horizontal),
+            2 => Some(Axis::Vertical),
+            _ => None,
+        }
+    }
+
+    pub fn set_under(&mut self, under: Option<Axis>) {
+        let code = match under {
+            None => 0,
+            Some(Axis::Horizontal) => 1,
+            Some(Axis::Vertical) => 2,
+        };
+        self.set_field(UNDER_SHIFT, code);
+    }
+
+    pub fn terrain(&self) -> Terrain {
+        match self.field(TERRAIN_SHIFT) {
+            0 => Terrain::Floor,
+            1 => Terrain::Ice,
+            2 => Terrain::Mud,
+            _ => Terrain::Water,
+        }
+    }
+
+    pub fn set_terrain(&mut self, terrain: Terrain) {
+        self.set_field(TERRAIN_SHIFT, terrain as u8);
+    }
+
+    fn flag(&self, flag: u8) -> bool {
+        self.bits & flag != 0
+    }
+
+    fn set_flag(&mut self, flag: u8, on: bool) {
+        if on {
+            self.bits |= flag;
+        } else {
+            self.bits &= !flag;
+        }
+    }
+
+    /// the two bit field starting at bit `shift`
+    fn field(&self, shift: u8) -> u8 {
+        self.bits >> shift & 0b11
+    }
+
+    fn set_field(&mut self, shift: u8, value: u8) {
+        self.bits = self.bits & !(0b11 << shift) | value << shift;
+    }
+
+    /// whether going in `dir` passes underneath the cell rather than into it
+    pub fn tunnels(&self, dir: Direction) -> bool {
+        self.under().is_some() && self.under() == dir.axis()
+    }
+
+    /// colour the cell is painted, showing whether it's on the path
+    pub fn fill(&self) -> &'static str {
+        if self.clicked() {
+            "green"
+        } else if self.hinted() {
+            "gold"
+        } else if self.explored() {
+            "lightskyblue"
+        } else {
+            self.terrain().fill()
+        }
+    }
+}
+
+impl fmt::Debug for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Cell")
+            .field("clicked", &self.clicked())
+            .field("hinted", &self.hinted())
+            .field("explored", &self.explored())
+            .field("active", &self.active())
+            .field("under", &self.under())
+            .field("terrain", &self.terrain())
+            .finish()
+    }
+}
+
+/// a Cell unpacked into one field each, the way saves write it
+#[cfg(feature = "serde")]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct CellFields {
+    clicked: bool,
+    hinted: bool,
+    explored: bool,
+    active: bool,
+    under: Option<Axis>,
+    terrain: Terrain,
+}
+
+#[cfg(feature = "serde")]
+impl From<CellFields> for Cell {
+    fn from(fields: CellFields) -> Self {
+        let mut cell = Cell { bits: 0 };
+        cell.set_clicked(fields.clicked);
+        cell.set_hinted(fields.hinted);
+        cell.set_explored(fields.explored);
+        cell.set_active(fields.active);
+        cell.set_under(fields.under);
+        cell.set_terrain(fields.terrain);
+        cell
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Cell> for CellFields {
+    fn from(cell: Cell) -> Self {
+        CellFields {
+            clicked: cell.clicked(),
+            hinted: cell.hinted(),
+            explored: cell.explored(),
+            active: cell.active(),
+            under: cell.under(),
+            terrain: cell.terrain(),
+        }
+    }
+}
+
+/// the walls around a single cell, looked up from the Maze for its view
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellWalls {
+    /// one bit per direction, set where the wall is open
+    open: u16,
+}
+
+impl CellWalls {
+    pub fn from_fn(wall: impl Fn(Direction) -> Wall) -> Self {
+        let open = Direction::ALL
+            .iter()
+            .filter(|&&dir| wall(dir) == Wall::No)
+            .fold(0, |open, &dir| open | 1 << dir as usize);
+        CellWalls { open }
+    }
+
+    pub fn wall(&self, dir: Direction) -> Wall {
+        if self.open >> dir as usize & 1 == 1 {
+            Wall::No
+        } else {
+            Wall::Yes
+        }
+    }
+}
+
+/// an svg coordinate inside a cell
+type Point = (u32, u32);
+
+/// corners of a pointy-topped hexagon in an 80x92 box, as the two ends of
+/// the wall in each direction
+const HEX_WALLS: [(Direction, Point, Point); 6] = [
+    (Direction::UpRight, (40, 0), (80, 23)),
+    (Direction::Right, (80, 23), (80, 69)),
+    (Direction::DownRight, (80, 69), (40, 92)),
+    (Direction::DownLeft, (40, 92), (0, 69)),
+    (Direction::Left, (0, 69), (0, 23)),
+    (Direction::UpLeft, (0, 23), (40, 0)),
+];
+
+/// walls of a square cell as svg rects, (x, y) and (width, height)
+const SQUARE_WALLS: [(Direction, Point, Point); 4] = [
+    (Direction::Up, (0, 0), (80, 10)),
+    (Direction::Right, (70, 0), (10, 80)),
+    (Direction::Down, (0, 70), (80, 10)),
+    (Direction::Left, (0, 0), (10, 80)),
+];
+
+/// colour of openings that lead around to the far side of a wrapped Maze
+const WRAP_COLOR: &str = "orange";
+
+/// depth of a ring of a polar Maze, and radius of its centre cell
+pub const RING: usize = 40;
+
+/// svg path of the arc at `radius` running clockwise from angle `from` to
+/// angle `to`, both in turns clockwise from the top of the circle
+fn arc(radius: usize, from: f64, to: f64) -> String {
+    let (x0, y0) = polar_point(radius, from);
+    let (x1, y1) = polar_point(radius, to);
+    let large = if to - from > 0.5 { 1 } else { 0 };
+    format!("M {x0:.1} {y0:.1} A {radius} {radius} 0 {large} 1 {x1:.1} {y1:.1}")
+}
+
+/// svg coordinates of a point `radius` out from the centre at `angle`
+/// turns clockwise from the top
+fn polar_point(radius: usize, angle: f64) -> (f64, f64) {
+    let theta = angle * std::f64::consts::TAU;
+    (radius as f64 * theta.sin(), -(radius as f64) * theta.cos())
+}
+
+/// a crossing of a weave Maze: the passage shaded in where it runs out
+/// from underneath the cell's own corridor, which has a railing each side
+fn bridge_view(under: Axis) -> Html {
+    // (x, y, width, height) of the two shaded ends, and the railing lines
+    let (ends, railings) = match under {
+        Axis::Vertical => (
+            [(20, 0, 40, 20), (20, 60, 40, 20)],
+            [((0, 20), (80, 20)), ((0, 60), (80, 60))],
+        ),
+        Axis::Horizontal => (
+            [(0, 20, 20, 40), (60, 20, 20, 40)],
+            [((20, 0), (20, 80)), ((60, 0), (60, 80))],
+        ),
+    };
+    html! {
+        <>
+            {
+                ends.iter().map(|&(x, y, w, h): &(u32, u32, u32, u32)| html! {
+                    <rect x={x.to_string()} y={y.to_string()} width={w.to_string()} height={h.to_string()}
+                    style="pointer-events:none;fill:black;fill-opacity:0.3;" />
+                }).collect::<Html>()
+            }
+            {
+                railings.iter().map(|&((x1, y1), (x2, y2)): &(Point, Point)| html! {
+                    <line x1={x1.to_string()} y1={y1.to_string()} x2={x2.to_string()} y2={y2.to_string()}
+                    style="pointer-events:none;stroke:blue;stroke-width:4;stroke-opacity:0.9" />
+                }).collect::<Html>()
+            }
+        </>
+    }
+}
+
+/// arrows marking the staircases leading out of a cell, centred on `at`
+fn stairs_view(walls: CellWalls, at: (f64, f64)) -> Html {
+    let arrows: String = [(Direction::Above, '▲'), (Direction::Below, '▼')]
+        .iter()
+        .filter(|(dir, _)| walls.wall(*dir) == Wall::No)
+        .map(|(_, arrow)| arrow)
+        .collect();
+    if arrows.is_empty() {
+        return html! {};
+    }
+    html! {
+        <text x={ format!("{:.1}", at.0) } y={ format!("{:.1}", at.1) }
+        text-anchor="middle" dominant-baseline="central"
+        style="pointer-events:none;font-size:24px;fill:darkblue;">
+            { arrows }
+        </text>
+    }
+}
+
+/// the two ends of the path through a Maze
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum End {
+    Start,
+    Goal,
+}
+
+/// a ring marking the start cell, or a filled in one marking the goal,
+/// centred on `at`
+fn end_view(end: End, at: (f64, f64)) -> Html {
+    let (color, fill) = match end {
+        End::Start => ("green", 0.0),
+        End::Goal => ("red", 0.4),
+    };
+    html! {
+        <circle cx={ format!("{:.1}", at.0) } cy={ format!("{:.1}", at.1) } r="18"
+        style={format!("pointer-events:none;fill:{0};fill-opacity:{1};stroke:{0};stroke-width:4;", color, fill)} />
+    }
+}
+
+/// colour a cell is painted: green on the path, otherwise its heat while
+/// the heatmap is showing, running from blue near the end it measures from
+/// to red farthest away
+fn fill(props: &CellViewProps) -> String {
+    match props.heat {
+        Some(heat) if !props.cell.clicked() => format!("hsl({:.0},100%,50%)", 240.0 * (1.0 - heat)),
+        _ => props.cell.fill().to_string(),
+    }
 }
 
 pub enum Msg {
@@ -25,8 +396,20 @@ pub struct CellView;
 #[derive(Clone, Debug, PartialEq, Properties)]
 pub struct CellViewProps {
     pub cell: Cell,
-    pub pos: (usize, usize),
-    pub cell_clicked: Callback<(usize, usize)>,
+    pub walls: CellWalls,
+    pub pos: Position,
+    pub topology: Topology,
+    /// open walls leading around to the far side of a wrapped Maze
+    #[prop_or_default]
+    pub wrapped: Vec<Direction>,
+    /// set on the start and goal cells
+    #[prop_or_default]
+    pub end: Option<End>,
+    /// distance from the end the heatmap measures from, 0.0 at it and 1.0
+    /// at the farthest cell, while the heatmap is showing
+    #[prop_or_default]
+    pub heat: Option<f64>,
+    pub cell_clicked: Callback<Position>,
 }
 
 impl Component for CellView {
@@ -47,50 +430,221 @@ impl Component for CellView {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        match ctx.props().topology {
+            Topology::Square => self.square_view(ctx),
+            Topology::Hex => self.hex_view(ctx),
+            Topology::Polar => self.polar_view(ctx),
+        }
+    }
+}
+
+impl CellView {
+    fn square_view(&self, ctx: &Context<Self>) -> Html {
         let link = ctx.link();
+        if !ctx.props().cell.active() {
+            return html! { <svg width="80" height="80"></svg> };
+        }
         html! {
             <svg width="80" height="80">
                 // draw cell
-                if ctx.props().cell.clicked{
-                    <rect x="0" y="0" width="80" height="80"
-                    onclick={link.callback(|_| Msg::Click)}
-                    style="fill:green;fill-opacity:0.3;"/>
-                } else{
-                    <rect x="0" y="0" width="80" height="80"
-                    onclick={link.callback(|_| Msg::Click)}
-                    style="fill:grey;fill-opacity:0.3;"/>
-                }
+                <rect x="0" y="0" width="80" height="80"
+                onclick={link.callback(|_| Msg::Click)}
+                style={format!("fill:{};fill-opacity:0.3;", fill(ctx.props()))}/>
 
                 // draw walls
-                if ctx.props().cell.left == Wall::Yes{
+                if ctx.props().walls.wall(Direction::Left) == Wall::Yes{
                     <rect x="0" y="0" width="10" height="80"
                     style="fill:blue;stroke:black;stroke-width:5;fill-opacity:0.9;stroke-opacity:0.9" />
                 } else {
                     <rect x="0" y="0" width="10" height="80"
                     style="fill:blue;stroke:black;stroke-width:5;fill-opacity:0.1;stroke-opacity:0.1" />
                 }
-                if ctx.props().cell.right == Wall::Yes{
+                if ctx.props().walls.wall(Direction::Right) == Wall::Yes{
                     <rect x="70" y="0" width="10" height="80"
                     style="fill:blue;stroke:black;stroke-width:5;fill-opacity:0.9;stroke-opacity:0.9" />
                 } else{
                     <rect x="70" y="0" width="10" height="80"
                     style="fill:blue;stroke:black;stroke-width:5;fill-opacity:0.1;stroke-opacity:0.1" />
                 }
-                if ctx.props().cell.up == Wall::Yes{
+                if ctx.props().walls.wall(Direction::Up) == Wall::Yes{
                     <rect x="0" y="0" width="80" height="10"
                     style="fill:blue;stroke:black;stroke-width:5;fill-opacity:0.9;stroke-opacity:0.9" />
                 }else{
                     <rect x="0" y="0" width="80" height="10"
                     style="fill:blue;stroke:black;stroke-width:5;fill-opacity:0.1;stroke-opacity:0.1" />
                 }
-                if ctx.props().cell.down == Wall::Yes{
+                if ctx.props().walls.wall(Direction::Down) == Wall::Yes{
                     <rect x="0" y="70" width="80" height="10"
                     style="fill:blue;stroke:black;stroke-width:5;fill-opacity:0.9;stroke-opacity:0.9" />
                 }else{
                     <rect x="0" y="70" width="80" height="10"
                     style="fill:blue;stroke:black;stroke-width:5;fill-opacity:0.1;stroke-opacity:0.1" />
                 }
+
+                // draw the bridge over a passage tunnelling underneath
+                if let Some(axis) = ctx.props().cell.under() {
+                    { bridge_view(axis) }
+                }
+
+                // draw openings that wrap around
+                {
+                    SQUARE_WALLS.iter().filter(|(dir, _, _)| ctx.props().wrapped.contains(dir)).map(|&(_, (x, y), (w, h))| html! {
+                        <rect x={x.to_string()} y={y.to_string()} width={w.to_string()} height={h.to_string()}
+                        style={format!("fill:{};fill-opacity:0.8;", WRAP_COLOR)} />
+                    }).collect::<Html>()
+                }
+
+                // draw start or goal
+                if let Some(end) = ctx.props().end {
+                    { end_view(end, (40.0, 40.0)) }
+                }
+
+                // draw stairs
+                { stairs_view(ctx.props().walls, (40.0, 40.0)) }
             </svg>
         }
     }
+
+    /// hexagons overlap the rows above and below them, so only the painted
+    /// shape takes clicks
+    fn hex_view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let cell = ctx.props().cell;
+        if !cell.active() {
+            return html! { <svg width="80" height="92"></svg> };
+        }
+        let fill = fill(ctx.props());
+        html! {
+            <svg width="80" height="92" style="pointer-events:none;">
+                // draw cell
+                <polygon points="40,0 80,23 80,69 40,92 0,69 0,23"
+                onclick={link.callback(|_| Msg::Click)}
+                style={format!("pointer-events:auto;fill:{};fill-opacity:0.3;", fill)}/>
+
+                // draw walls
+                {
+                    HEX_WALLS.iter().map(|&(dir, (x1, y1), (x2, y2))| {
+                        let (color, opacity) = if ctx.props().walls.wall(dir) == Wall::Yes {
+                            ("blue", 0.9)
+                        } else if ctx.props().wrapped.contains(&dir) {
+                            (WRAP_COLOR, 0.8)
+                        } else {
+                            ("blue", 0.1)
+                        };
+                        html! {
+                            <line x1={x1.to_string()} y1={y1.to_string()}
+                            x2={x2.to_string()} y2={y2.to_string()}
+                            style={format!("stroke:{};stroke-width:10;stroke-opacity:{}", color, opacity)} />
+                        }
+                    }).collect::<Html>()
+                }
+
+                // draw start or goal
+                if let Some(end) = ctx.props().end {
+                    { end_view(end, (40.0, 46.0)) }
+                }
+
+                // draw stairs
+                { stairs_view(ctx.props().walls, (40.0, 46.0)) }
+            </svg>
+        }
+    }
+
+    /// a ring cell drawn around the centre of the MazeView's svg: a sector
+    /// of an annulus, or a disc for the centre cell
+    fn polar_view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let Position { r, c, .. } = ctx.props().pos;
+        let len = ring_len(r);
+        let (from, to) = (c as f64 / len as f64, (c + 1) as f64 / len as f64);
+        let (inner, outer) = (r * RING, (r + 1) * RING);
+        let fill = fill(ctx.props());
+
+        let shape = if r == 0 {
+            html! {
+                <circle cx="0" cy="0" r={ outer.to_string() }
+                onclick={link.callback(|_| Msg::Click)}
+                style={format!("fill:{};fill-opacity:0.3;", fill)}/>
+            }
+        } else {
+            let (x0, y0) = polar_point(inner, from);
+            let (x1, y1) = polar_point(outer, from);
+            let (x2, y2) = polar_point(outer, to);
+            let (x3, y3) = polar_point(inner, to);
+            let large = if to - from > 0.5 { 1 } else { 0 };
+            // out along one edge, clockwise around the outside, back in
+            // and counter-clockwise around the inside
+            let sector = format!(
+                "M {x0:.1} {y0:.1} L {x1:.1} {y1:.1} A {outer} {outer} 0 {large} 1 {x2:.1} {y2:.1} \
+                 L {x3:.1} {y3:.1} A {inner} {inner} 0 {large} 0 {x0:.1} {y0:.1} Z"
+            );
+            html! {
+                <path d={ sector }
+                onclick={link.callback(|_| Msg::Click)}
+                style={format!("fill:{};fill-opacity:0.3;", fill)}/>
+            }
+        };
+
+        // outward walls split in two where the next ring splits its cells
+        let middle = (from + to) / 2.0;
+        let centre = if r == 0 {
+            (0.0, 0.0)
+        } else {
+            polar_point(inner + RING / 2, middle)
+        };
+        let mut walls = vec![];
+        if r > 0 {
+            walls.push((Direction::Up, arc(inner, from, to)));
+        }
+        if ring_len(r + 1) > len {
+            walls.push((Direction::DownLeft, arc(outer, from, middle)));
+            walls.push((Direction::DownRight, arc(outer, middle, to)));
+        } else {
+            walls.push((Direction::Down, arc(outer, from, to)));
+        }
+        if len > 1 {
+            for (dir, angle) in [(Direction::Left, from), (Direction::Right, to)] {
+                let (x0, y0) = polar_point(inner, angle);
+                let (x1, y1) = polar_point(outer, angle);
+                walls.push((dir, format!("M {:.1} {:.1} L {:.1} {:.1}", x0, y0, x1, y1)));
+            }
+        }
+
+        html! {
+            <g>
+                // draw cell
+                { shape }
+
+                // draw walls
+                {
+                    walls.into_iter().map(|(dir, d)| {
+                        let opacity = if ctx.props().walls.wall(dir) == Wall::Yes { 0.9 } else { 0.1 };
+                        html! {
+                            <path d={ d }
+                            style={format!("fill:none;pointer-events:none;stroke:blue;stroke-width:6;stroke-opacity:{}", opacity)} />
+                        }
+                    }).collect::<Html>()
+                }
+
+                // draw start or goal
+                if let Some(end) = ctx.props().end {
+                    { end_view(end, centre) }
+                }
+
+                // draw stairs
+                { stairs_view(ctx.props().walls, centre) }
+            </g>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rough_terrain_costs_more_than_floor() {
+        assert_eq!(Terrain::Floor.cost(), 1);
+        assert_eq!(Terrain::ROUGH.map(Terrain::cost), [2, 3, 5]);
+    }
 }
@@ -0,0 +1,214 @@
+use rand::SeedableRng;
+use std::collections::HashSet;
+
+use crate::cell::{Cell, Wall};
+use crate::generator::{DisjointSet, EllerRows, MazeRng};
+use crate::maze::walls::Walls;
+use crate::maze::Maze;
+use crate::position::{Direction, Position};
+
+/// endless climb: the Maze is a window onto an Eller maze that keeps
+/// growing upward, and rows that scroll off the bottom are thrown away.
+/// The Maze's start is the cell the player's path has to grow from, and as
+/// there's no goal to reach the goal just sits on the start
+pub struct Climb {
+    rows: EllerRows,
+    rng: MazeRng,
+    /// rows scrolled off the bottom so far
+    pub scrolled: usize,
+    /// best height reached above the very first row
+    pub height: usize,
+}
+
+impl Climb {
+    /// start a climb, returning the first m×n window onto it
+    pub fn new(m: usize, n: usize, seed: u64) -> (Self, Maze) {
+        let mut climb = Climb {
+            rows: EllerRows::new(n),
+            rng: MazeRng::seed_from_u64(seed),
+            scrolled: 0,
+            height: 0,
+        };
+        let mut maze = Maze::new(0, n);
+        maze.seed = seed;
+        for _ in 0..m {
+            climb.push_row(&mut maze);
+        }
+        maze.start = Position {
+            f: 0,
+            r: m - 1,
+            c: 0,
+        };
+        maze.goal = maze.start;
+        (climb, maze)
+    }
+
+    /// check how far the path climbs and scroll the window so the top of the
+    /// path sits at mid-height, returning whether the player made progress
+    pub fn advance(&mut self, maze: &mut Maze, path: &mut HashSet<Position>) -> bool {
+        if !path.contains(&maze.start) {
+            return false;
+        }
+        let reached = maze.reachable(maze.start, path);
+        let top = reached.iter().map(|p| p.r).min().unwrap();
+
+        let height = self.scrolled + (maze.m - 1 - top);
+        if height <= self.height {
+            return false;
+        }
+        self.height = height;
+
+        let shift = (maze.m / 2).saturating_sub(top);
+        if shift > 0 {
+            // the path passes through every row between start and top, so
+            // some reached cell survives in what becomes the bottom row
+            if maze.start.r + shift >= maze.m {
+                let r = maze.m - 1 - shift;
+                maze.start = *reached.iter().find(|p| p.r == r).unwrap();
+            }
+            maze.start.r += shift;
+            maze.goal = maze.start;
+            self.scroll(maze, path, shift);
+        }
+        true
+    }
+
+    /// drop `shift` rows off the bottom of the window and push as many fresh
+    /// rows onto the top, moving the path along with the cells
+    fn scroll(&mut self, maze: &mut Maze, path: &mut HashSet<Position>, shift: usize) {
+        let n = maze.n;
+        // the new bottom row is walled off below as there's nothing there
+        maze.m -= shift;
+        maze.cells.truncate(maze.m * n);
+        self.repair(maze);
+        for _ in 0..shift {
+            self.push_row(maze);
+        }
+
+        *path = path
+            .iter()
+            .filter(|p| p.r + shift < maze.m)
+            .map(|p| Position {
+                r: p.r + shift,
+                ..*p
+            })
+            .collect();
+        self.scrolled += shift;
+    }
+
+    /// link the next row from the stream on above the window's top row
+    fn push_row(&mut self, maze: &mut Maze) {
+        let row = self.rows.next_row(&mut self.rng);
+        let n = maze.n;
+        // every row moves down one to make room on top, walls and all
+        let mut grown = Maze {
+            m: maze.m + 1,
+            cells: [vec![Cell::closed(); n], maze.cells.clone()].concat(),
+            walls: Walls::default(),
+            ..maze.clone()
+        };
+        for p in maze.positions() {
+            for dir in [Direction::Right, Direction::Down] {
+                if maze.wall(p, dir) == Wall::No {
+                    grown.set_wall(Position { r: p.r + 1, ..p }, dir, Wall::No);
+                }
+            }
+        }
+        for c in 0..n {
+            let p = Position { f: 0, r: 0, c };
+            if row.right[c] {
+                grown.set_wall(p, Direction::Right, Wall::No);
+            }
+            if row.down[c] && maze.m > 0 {
+                grown.set_wall(p, Direction::Down, Wall::No);
+            }
+        }
+        *maze = grown;
+    }
+
+    /// cells that were only linked through dropped rows can end up walled
+    /// off from the top. Knock through to a neighboring region until every
+    /// region reaches the top row again, then let the stream know which top
+    /// cells are still linked so each region keeps a way up
+    fn repair(&mut self, maze: &mut Maze) {
+        let n = maze.n;
+        let mut regions = DisjointSet::new(maze.m * n);
+        for r in 0..maze.m {
+            for c in 0..n {
+                let p = Position { f: 0, r, c };
+                for (dir, new_pos) in maze.neighbors(p) {
+                    if maze.wall(p, dir) == Wall::No {
+                        regions.union(r * n + c, new_pos.r * n + new_pos.c);
+                    }
+                }
+            }
+        }
+
+        let mut stranded = true;
+        while stranded {
+            stranded = false;
+            let on_top: HashSet<usize> = (0..n).map(|c| regions.find(c)).collect();
+            for i in n..maze.m * n {
+                if on_top.contains(&regions.find(i)) {
+                    continue;
+                }
+                stranded = true;
+                let p = Position {
+                    f: 0,
+                    r: i / n,
+                    c: i % n,
+                };
+                let exit = maze.neighbors(p).into_iter().find(|(_, new_pos)| {
+                    regions.find(new_pos.r * n + new_pos.c) != regions.find(i)
+                });
+                if let Some((dir, new_pos)) = exit {
+                    maze.carve(p, dir);
+                    regions.union(i, new_pos.r * n + new_pos.c);
+                }
+            }
+        }
+
+        let components: Vec<usize> = (0..n).map(|c| regions.find(c)).collect();
+        self.rows.regroup(&components);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::validate::MazeError;
+
+    /// every cell of the window that some path from `from` leads to
+    fn linked(maze: &Maze, from: Position) -> HashSet<Position> {
+        let all: HashSet<Position> = maze.positions().collect();
+        maze.reachable(from, &all)
+    }
+
+    #[test]
+    fn scrolled_rows_stay_linked_to_the_top() {
+        let (mut climb, mut maze) = Climb::new(8, 7, 3);
+        for step in 1..=30 {
+            // clicking every cell climbs as far as the window goes
+            let mut path: HashSet<Position> = maze.positions().collect();
+            assert!(climb.advance(&mut maze, &mut path), "step {}", step);
+            assert_eq!((maze.m, maze.cells.len()), (8, 56));
+
+            // the walls agree on both sides across the seam, and every
+            // cell still has a way up to the newest row
+            let errors = maze.validate().err().unwrap_or_default();
+            assert!(
+                errors
+                    .iter()
+                    .all(|err| matches!(err, MazeError::Unreachable { .. })),
+                "step {}: {:?}",
+                step,
+                errors
+            );
+            let top: HashSet<Position> = (0..maze.n)
+                .flat_map(|c| linked(&maze, Position { f: 0, r: 0, c }))
+                .collect();
+            assert_eq!(top.len(), maze.cells.len(), "step {}", step);
+        }
+        assert!(climb.height >= 30);
+    }
+}
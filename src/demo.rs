@@ -0,0 +1,75 @@
+use crate::game::Game;
+use crate::maze::Maze;
+use crate::position::Position;
+use crate::solver::{self, Solution};
+use crate::topology::Topology;
+
+/// levels the demo takes its mazes from. They're all perfect mazes, which
+/// every solver gets through
+const DEMO_LEVELS: usize = 8;
+
+/// ticks the finished path stays up before the next round
+const HOLD_TICKS: usize = 40;
+
+/// grids the demo goes through, each one with every solver in turn
+const TOPOLOGIES: [Topology; 3] = [Topology::Square, Topology::Hex, Topology::Polar];
+
+/// attract mode: a solver working its way through a maze one cell a tick.
+/// The cells it explores are marked first, then the path it found is
+/// clicked just like a player would
+pub struct Demo {
+    /// rounds shown before this one, each with the next solver
+    pub round: usize,
+    /// name of the solver at work
+    pub solver: &'static str,
+    pub solution: Solution,
+    /// ticks taken so far this round
+    ticks: usize,
+}
+
+impl Demo {
+    /// start round `round`, returning the maze it's solving
+    pub fn new(round: usize, seed: u64) -> (Self, Maze) {
+        let topology = TOPOLOGIES[(round / solver::NAMES.len()) % TOPOLOGIES.len()];
+        let mut game = Game::with_seed(seed.wrapping_add(round as u64), topology);
+        game.level = 1 + round % DEMO_LEVELS;
+        let maze = game.get_maze();
+
+        let name = solver::NAMES[round % solver::NAMES.len()];
+        let solution = solver::from_name(name)
+            .unwrap()
+            .solve(&maze, maze.start, maze.goal)
+            .expect("perfect mazes are always solved");
+        let demo = Demo {
+            round,
+            solver: name,
+            solution,
+            ticks: 0,
+        };
+        (demo, maze)
+    }
+
+    /// show the next explored cell, or once they're all shown the next cell
+    /// of the path, returning it
+    pub fn advance(&mut self, maze: &mut Maze) -> Option<Position> {
+        let explored = self.solution.visited.len();
+        let shown = if self.ticks < explored {
+            let p = self.solution.visited[self.ticks];
+            maze.cell_mut(p).set_explored(true);
+            Some(p)
+        } else {
+            let p = self.solution.path.get(self.ticks - explored).copied();
+            if let Some(p) = p {
+                maze.cell_mut(p).set_clicked(true);
+            }
+            p
+        };
+        self.ticks += 1;
+        shown
+    }
+
+    /// whether the round is done, path shown and held up long enough
+    pub fn over(&self) -> bool {
+        self.ticks >= self.solution.visited.len() + self.solution.path.len() + HOLD_TICKS
+    }
+}
@@ -1,6 +1,77 @@
-use rand::{thread_rng, Rng};
+use rand::{thread_rng, Rng, RngCore, SeedableRng};
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
-use crate::maze::Maze;
+use crate::generator::{self, below, GrowingTree, MazeGenerator, MazeRng};
+use crate::mask::Mask;
+use crate::maze::analysis::Analysis;
+use crate::maze::{Maze, Placement};
+use crate::position::Position;
+use crate::solver::{Dijkstra, MazeSolver};
+use crate::topology::Topology;
+
+/// levels carved by recursive division for its long, easy corridors
+const EARLY_LEVELS: usize = 3;
+
+/// levels the growing tree takes to lean all the way into long backtracker
+/// corridors once the early levels are done
+const TREE_RAMP_LEVELS: usize = 16;
+
+/// levels from which mazes start getting loops
+const BRAID_LEVEL: usize = 10;
+
+/// levels from which cells start getting covered in rough terrain, once
+/// loops give the path a choice of ways around it
+const TERRAIN_LEVEL: usize = 12;
+
+/// how much more than the cheapest way to the goal, in percent, a winning
+/// path may cost unless the game says otherwise
+const COST_SLACK: usize = 25;
+
+/// first level of a square game that's an open cave instead of corridors,
+/// and every this many levels after it
+const CAVE_LEVEL: usize = 14;
+const CAVE_EVERY: usize = 7;
+
+/// every this many levels the maze is carved inside a shape
+const SHAPE_EVERY: usize = 5;
+
+/// every this many levels the maze is stacked into floors joined by stairs
+const STACK_EVERY: usize = 4;
+
+/// most floors a stacked level gets
+const MAX_FLOORS: usize = 4;
+
+/// every this many levels after the early ones the maze wraps around its
+/// edges, unless it's carved inside a shape
+const WRAP_EVERY: usize = 3;
+
+/// solution length asked of the first level, and how much longer each
+/// level after it asks for
+const SOLUTION_BASE: usize = 10;
+const SOLUTION_PER_LEVEL: f64 = 1.5;
+
+/// mazes carved per level looking for one in its solution band
+const MAX_TRIES: usize = 12;
+
+/// smallest grid a level shrinks down to while looking for its band
+const MIN_SIZE: usize = 3;
+
+/// a tower for the shaped levels
+const TOWER: &str = "
+XX.XX.XX
+XXXXXXXX
+.XXXXXX.
+.XX..XX.
+.XXXXXX.
+.XXXXXX.
+.XX..XX.
+.XXXXXX.
+XXXXXXXX
+";
+
+/// generators cycled through once the early levels are done
+const LEVEL_GENERATORS: [&str; 5] = ["growing-tree", "prim", "kruskal", "wilson", "weave"];
 
 pub enum GameStatus {
     Alive,
@@ -8,35 +79,243 @@ pub enum GameStatus {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     pub level: usize,
     pub lives: usize,
+    /// seed every maze of the game is derived from
+    #[cfg_attr(feature = "serde", serde(with = "crate::save::seed"))]
+    pub seed: u64,
+    /// grid every maze of the game is laid out on
+    pub topology: Topology,
+    /// when set, a path only wins if it costs at most this many percent
+    /// more than the cheapest way through the maze
+    pub cost_slack: Option<usize>,
 }
 
 impl Game {
-    pub fn new() -> Self {
-        Self { level: 1, lives: 3 }
+    pub fn new(topology: Topology) -> Self {
+        Self::with_seed(thread_rng().gen(), topology)
+    }
+
+    pub fn with_seed(seed: u64, topology: Topology) -> Self {
+        Self {
+            level: 1,
+            lives: 3,
+            seed,
+            topology,
+            cost_slack: Some(COST_SLACK),
+        }
+    }
+
+    /// seed for the current maze. Each level and life gets its own ChaCha
+    /// stream, so a reset hands out a different maze than the one given up on
+    pub fn maze_seed(&self) -> u64 {
+        let mut rng = MazeRng::seed_from_u64(self.seed);
+        rng.set_stream(((self.level as u64) << 32) | self.lives as u64);
+        rng.next_u64()
+    }
+
+    /// name of the generator that carves the current level
+    pub fn generator_name(&self) -> &'static str {
+        if self.is_cave() {
+            "cave"
+        } else if self.level <= EARLY_LEVELS {
+            "recursive-division"
+        } else {
+            LEVEL_GENERATORS[(self.level - EARLY_LEVELS - 1) % LEVEL_GENERATORS.len()]
+        }
+    }
+
+    /// growing tree bias for the current level, from Prim style right after
+    /// the early levels to recursive backtracker style by the end of the ramp
+    pub fn tree_bias(&self) -> f64 {
+        let ramp = self.level.saturating_sub(EARLY_LEVELS + 1) as f64 / TREE_RAMP_LEVELS as f64;
+        f64::min(ramp, 1.0)
+    }
+
+    /// whether the current level is a cave. Caves are smoothed out over the
+    /// eight cells around each one, so they only come up in square games
+    /// and take the place of any shape, floors or wrapping
+    pub fn is_cave(&self) -> bool {
+        self.topology == Topology::Square
+            && self.level >= CAVE_LEVEL
+            && (self.level - CAVE_LEVEL).is_multiple_of(CAVE_EVERY)
+    }
+
+    /// shape the current level is carved in, if it isn't a plain rectangle
+    pub fn shape(&self, m: usize, n: usize) -> Option<Mask> {
+        if !self.level.is_multiple_of(SHAPE_EVERY) || self.is_cave() {
+            return None;
+        }
+        match (self.level / SHAPE_EVERY) % 3 {
+            1 => Some(Mask::circle(m, n)),
+            2 => Some(Mask::heart(m, n)),
+            _ => Some(Mask::from_ascii(TOWER)),
+        }
+    }
+
+    /// floors the maze of the current level is stacked into, more of them
+    /// the later the level
+    pub fn floors(&self) -> usize {
+        if !self.level.is_multiple_of(STACK_EVERY) || self.is_cave() {
+            return 1;
+        }
+        usize::min(2 + self.level / (3 * STACK_EVERY), MAX_FLOORS)
+    }
+
+    /// whether the maze of the current level wraps around its edges, which
+    /// round and shaped mazes never do
+    pub fn wraps(&self) -> bool {
+        self.level > EARLY_LEVELS
+            && self.level.is_multiple_of(WRAP_EVERY)
+            && !self.level.is_multiple_of(SHAPE_EVERY)
+            && self.topology != Topology::Polar
+            && !self.is_cave()
+    }
+
+    /// fraction of dead ends opened into loops, ramping up to half of
+    /// them over the late levels
+    pub fn braid_factor(&self) -> f64 {
+        if self.level < BRAID_LEVEL {
+            0.0
+        } else {
+            f64::min(0.1 * (self.level - BRAID_LEVEL + 1) as f64, 0.5)
+        }
     }
 
-    /// programmatically generate mazes based on level
+    /// share of cells covered in rough terrain, ramping up to a third of
+    /// them over the late levels
+    pub fn terrain_fraction(&self) -> f64 {
+        if self.level < TERRAIN_LEVEL {
+            0.0
+        } else {
+            f64::min(0.05 * (self.level - TERRAIN_LEVEL + 1) as f64, 0.33)
+        }
+    }
+
+    /// most a path through `maze` may cost and still win, if the game sets
+    /// a limit at all. Mazes without terrain have none, any way through
+    /// them wins. It takes a full search, so is worked out once per maze
+    pub fn cost_limit(&self, maze: &Maze) -> Option<usize> {
+        if !maze.is_weighted() {
+            return None;
+        }
+        let slack = self.cost_slack?;
+        let best = Dijkstra.solve(maze, maze.start, maze.goal)?.cost(maze);
+        Some(best * (100 + slack) / 100)
+    }
+
+    /// what the clicked cells of `path` cost, every one but the start
+    /// charged for its terrain whether it leads to the goal or not
+    pub fn path_cost(maze: &Maze, path: &HashSet<Position>) -> usize {
+        path.iter()
+            .filter(|&&p| p != maze.start)
+            .map(|&p| maze.cell(p).terrain().cost())
+            .sum()
+    }
+
+    /// whether the clicked cells of `path` lead from start to goal, costing
+    /// no more than `limit` when the maze has one. Worked out by the caller
+    /// with `cost_limit` once per maze
+    pub fn path_wins(maze: &Maze, path: &HashSet<Position>, limit: Option<usize>) -> bool {
+        path.contains(&maze.start)
+            && maze.is_connected(maze.start, maze.goal, path)
+            && limit.is_none_or(|limit| Self::path_cost(maze, path) <= limit)
+    }
+
+    /// range the length of the current level's solution should land in,
+    /// counted in cells from start to goal
+    pub fn solution_band(&self) -> RangeInclusive<usize> {
+        let target = SOLUTION_BASE + (SOLUTION_PER_LEVEL * self.level as f64) as usize;
+        target * 3 / 4..=target * 5 / 4
+    }
+
+    /// programmatically generate mazes based on level. Mazes are carved
+    /// until one has a solution in the level's band, growing or shrinking
+    /// the grid after each miss. If none lands in it the closest is kept
     pub fn get_maze(&self) -> Maze {
-        // based on current level -> generate random MxN maze
+        // based on current level -> pick a random MxN size around a target
         // w/ simple formula:
         // level <= 10 -> 0.6 * level + 4
-        // level >  10 -> 0.2 * level + 4
+        // level >  10 -> 0.2 * (level - 10) + 10, carrying on from level 10
         let mut tgt: usize = ((0.6 * self.level as f32) as usize) + 4;
         if self.level > 10 {
-            tgt = ((0.6 * self.level as f32) as usize) + 4;
+            tgt = ((0.2 * (self.level - 10) as f32) as usize) + 10;
         }
         let diff: usize = usize::max((0.4 * tgt as f32) as usize, 1);
-        let (m, n) = (
-            thread_rng().gen_range((tgt - diff)..(tgt + diff)),
-            thread_rng().gen_range((tgt - diff)..(tgt + diff)),
+        let seed = self.maze_seed();
+        let mut rng = MazeRng::seed_from_u64(seed);
+        let (mut m, mut n) = (
+            tgt - diff + below(&mut rng, 2 * diff),
+            tgt - diff + below(&mut rng, 2 * diff),
         );
-        let max_depth = thread_rng().gen_range(6..(m * n));
 
-        let mut maze = Maze::new(m, n);
-        maze.cut_up_maze(max_depth);
+        let band = self.solution_band();
+        let mut best: Option<(usize, Maze)> = None;
+        for _ in 0..MAX_TRIES {
+            let mut maze = self.carve_maze(m, n, &mut rng);
+            maze.seed = seed;
+            let length = Analysis::of(&maze).solution_length.unwrap_or(0);
+            // bigger grids take bigger steps to change the solution much
+            let miss = if length < *band.start() {
+                m += 1 + m / 8;
+                n += 1 + n / 8;
+                band.start() - length
+            } else if length > *band.end() {
+                m = usize::max(m - 1 - m / 8, MIN_SIZE);
+                n = usize::max(n - 1 - n / 8, MIN_SIZE);
+                length - band.end()
+            } else {
+                return maze;
+            };
+            if best.as_ref().is_none_or(|(best_miss, _)| miss < *best_miss) {
+                best = Some((miss, maze));
+            }
+        }
+        best.unwrap().1
+    }
+
+    /// carve one maze for the current level on an m x n grid
+    fn carve_maze(&self, m: usize, n: usize, rng: &mut MazeRng) -> Maze {
+        let mut maze = match (self.topology, self.shape(m, n)) {
+            // rings are round already, there are just enough of them to
+            // hold as many cells as the m x n grid would
+            (Topology::Polar, _) => {
+                let rings = (1..)
+                    .find(|&rings| Topology::Polar.row_start(rings, 0) >= m * n)
+                    .unwrap();
+                Maze::polar(rings)
+            }
+            (topology, Some(mask)) => Maze {
+                topology,
+                ..Maze::with_mask(&mask)
+            },
+            // hex rows only line up across the top and bottom in pairs
+            (topology, None) if self.wraps() => Maze {
+                topology,
+                wrap: true,
+                ..Maze::new(m + m % 2, n)
+            },
+            (topology, None) => Maze {
+                topology,
+                ..Maze::new(m, n)
+            },
+        }
+        .with_floors(self.floors());
+        let generator: Box<dyn MazeGenerator> = match self.generator_name() {
+            "growing-tree" => Box::new(GrowingTree::with_bias(self.tree_bias())),
+            name => generator::from_name(name).unwrap(),
+        };
+        generator.generate(&mut maze, rng);
+        maze.braid(self.braid_factor(), rng);
+        maze.place_ends(Placement::Farthest);
+        // levels without terrain leave the RNG alone so their mazes don't
+        // shift around
+        if self.terrain_fraction() > 0.0 {
+            maze.scatter_terrain(self.terrain_fraction(), rng);
+        }
+        debug_assert_eq!(maze.validate().err(), None);
         maze
     }
 
@@ -53,3 +332,107 @@ impl Game {
         self.level += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Terrain;
+
+    #[test]
+    fn same_seed_gives_same_levels() {
+        for topology in [Topology::Square, Topology::Hex, Topology::Polar] {
+            for level in [1, 5, 8, 14] {
+                let game = Game {
+                    level,
+                    ..Game::with_seed(42, topology)
+                };
+                let again = Game {
+                    level,
+                    ..Game::with_seed(42, topology)
+                };
+                assert_eq!(game.get_maze(), again.get_maze(), "level {}", level);
+            }
+        }
+    }
+
+    #[test]
+    fn cave_levels_lead_to_the_goal() {
+        for seed in 0..4 {
+            for level in [14, 21, 28, 35] {
+                let game = Game {
+                    level,
+                    ..Game::with_seed(seed, Topology::Square)
+                };
+                assert!(game.is_cave());
+                let maze = game.get_maze();
+                assert!(maze.validate().is_ok(), "level {}", level);
+                assert!(maze.distances(maze.start)[maze.index(maze.goal)].is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn path_cost_decides_the_win() {
+        // a corridor across mud, with a dead end of water off the middle
+        let mut maze = Maze::new(2, 5);
+        let at = |r, c| Position { f: 0, r, c };
+        for c in 0..4 {
+            maze.carve(at(0, c), crate::position::Direction::Right);
+            maze.cell_mut(at(0, c + 1)).set_terrain(Terrain::Mud);
+        }
+        maze.carve(at(0, 2), crate::position::Direction::Down);
+        maze.cell_mut(at(1, 2)).set_terrain(Terrain::Water);
+        maze.start = at(0, 0);
+        maze.goal = at(0, 4);
+
+        let mut path: HashSet<Position> = (0..5).map(|c| at(0, c)).collect();
+        assert_eq!(Game::path_cost(&maze, &path), 12);
+        assert!(Game::path_wins(&maze, &path, None));
+        assert!(Game::path_wins(&maze, &path, Some(12)));
+        assert!(!Game::path_wins(&maze, &path, Some(11)));
+
+        // clicks off the way to the goal are charged for too
+        path.insert(at(1, 2));
+        assert_eq!(Game::path_cost(&maze, &path), 17);
+        assert!(!Game::path_wins(&maze, &path, Some(12)));
+
+        path.remove(&at(0, 3));
+        assert!(!Game::path_wins(&maze, &path, None));
+    }
+
+    #[test]
+    fn only_a_cheap_path_wins_a_terrain_level() {
+        for seed in 0..4 {
+            let game = Game {
+                level: 16,
+                ..Game::with_seed(seed, Topology::Square)
+            };
+            let maze = game.get_maze();
+            assert!(maze.is_weighted());
+            let limit = game.cost_limit(&maze);
+            assert!(limit.is_some());
+
+            let best = Dijkstra.solve(&maze, maze.start, maze.goal).unwrap();
+            let drawn: HashSet<Position> = best.path.iter().copied().collect();
+            assert!(Game::path_wins(&maze, &drawn, limit));
+
+            // clicking every cell reaches the goal, but not cheaply
+            let everything: HashSet<Position> = maze.positions().collect();
+            assert!(!Game::path_wins(&maze, &everything, limit));
+            assert!(Game::path_wins(&maze, &everything, None));
+        }
+    }
+
+    #[test]
+    fn levels_without_terrain_have_no_limit() {
+        for level in [10, 11] {
+            let game = Game {
+                level,
+                ..Game::with_seed(5, Topology::Square)
+            };
+            let maze = game.get_maze();
+            assert!(!maze.is_weighted());
+            assert_eq!(game.cost_limit(&maze), None);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+mod cave;
+mod eller;
+mod growing_tree;
+mod hunt_and_kill;
+mod kruskal;
+mod prim;
+mod recursive_division;
+mod weave;
+mod wilson;
+
+pub use cave::Cave;
+pub use eller::{Eller, EllerRows};
+pub use growing_tree::GrowingTree;
+pub use hunt_and_kill::HuntAndKill;
+pub use kruskal::{join_regions, DisjointSet, Kruskal};
+pub use prim::Prim;
+pub use recursive_division::RecursiveDivision;
+pub use weave::Weave;
+pub use wilson::Wilson;
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::maze::Maze;
+
+/// the RNG all maze randomness is drawn from. ChaCha8 gives the same
+/// stream for the same seed on every platform
+pub type MazeRng = ChaCha8Rng;
+
+/// uniform index below `n`. Drawn as a u32 so that wasm32 and 64-bit
+/// builds pull the same numbers out of the RNG
+pub fn below(rng: &mut MazeRng, n: usize) -> usize {
+    rng.gen_range(0..n as u32) as usize
+}
+
+/// an algorithm that fills a freshly constructed Maze with paths
+pub trait MazeGenerator {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng);
+}
+
+/// names of the generators that carve any kind of Maze, all accepted by
+/// `from_name`. "cave" is accepted too, but only carves plain Mazes
+pub const NAMES: [&str; 8] = [
+    "eller",
+    "growing-tree",
+    "hunt-and-kill",
+    "kruskal",
+    "prim",
+    "recursive-division",
+    "weave",
+    "wilson",
+];
+
+/// look up a generator with its default settings by name
+pub fn from_name(name: &str) -> Option<Box<dyn MazeGenerator>> {
+    match name {
+        "cave" => Some(Box::new(Cave::default())),
+        "eller" => Some(Box::new(Eller)),
+        "growing-tree" => Some(Box::new(GrowingTree::default())),
+        "hunt-and-kill" => Some(Box::new(HuntAndKill::default())),
+        "kruskal" => Some(Box::new(Kruskal)),
+        "prim" => Some(Box::new(Prim)),
+        "recursive-division" => Some(Box::new(RecursiveDivision)),
+        "weave" => Some(Box::new(Weave::default())),
+        "wilson" => Some(Box::new(Wilson)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::maze::validate::Layout;
+    use crate::topology::Topology;
+
+    /// a closed-off Maze of each topology
+    fn blank_mazes() -> [Maze; 3] {
+        [
+            Maze::new(12, 15),
+            Maze {
+                topology: Topology::Hex,
+                ..Maze::new(12, 15)
+            },
+            Maze::polar(8),
+        ]
+    }
+
+    #[test]
+    fn generators_carve_perfect_mazes() {
+        for name in NAMES {
+            let generator = from_name(name).unwrap();
+            for blank in blank_mazes() {
+                for seed in 0..4 {
+                    let mut maze = blank.clone();
+                    generator.generate(&mut maze, &mut MazeRng::seed_from_u64(seed));
+                    assert_eq!(
+                        maze.validate(),
+                        Ok(Layout::Perfect),
+                        "{} on {:?}, seed {}",
+                        name,
+                        maze.topology,
+                        seed
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_carves_same_maze() {
+        for name in NAMES {
+            let generator = from_name(name).unwrap();
+            for blank in blank_mazes() {
+                let carve = |seed| {
+                    let mut maze = blank.clone();
+                    generator.generate(&mut maze, &mut MazeRng::seed_from_u64(seed));
+                    maze
+                };
+                assert_eq!(carve(11), carve(11), "{} on {:?}", name, blank.topology);
+                assert_ne!(carve(11), carve(12), "{} on {:?}", name, blank.topology);
+            }
+        }
+    }
+}
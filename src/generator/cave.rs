@@ -0,0 +1,145 @@
+use rand::Rng;
+
+use super::{MazeGenerator, MazeRng};
+use crate::mask::Mask;
+use crate::maze::Maze;
+use crate::position::{Direction, Position};
+
+/// rock neighbors, out of the eight around it, that turn an open cell
+/// into rock while smoothing, and that keep a rock cell rock
+const ERODE: usize = 5;
+const KEEP: usize = 4;
+
+/// the eight cells around one, diagonals included
+const AROUND: [Direction; 8] = [
+    Direction::Up,
+    Direction::UpRight,
+    Direction::Right,
+    Direction::DownRight,
+    Direction::Down,
+    Direction::DownLeft,
+    Direction::Left,
+    Direction::UpLeft,
+];
+
+/// cellular automata caves: scatter rock over the grid, then smooth it a
+/// few times so each cell takes after most of its neighbors. The largest
+/// open cave is kept, with every wall inside it knocked down, and the rock
+/// around it is masked out of the Maze. The smoothing looks at the eight
+/// cells around each one, so only plain Mazes can be carved into caves
+pub struct Cave {
+    /// chance of each cell starting out as rock
+    pub fill: f64,
+    /// rounds of smoothing
+    pub steps: usize,
+}
+
+impl Default for Cave {
+    fn default() -> Self {
+        Self {
+            fill: 0.45,
+            steps: 4,
+        }
+    }
+}
+
+impl MazeGenerator for Cave {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        assert!(
+            maze.is_plain(),
+            "caves are carved on a single unwrapped floor of square cells"
+        );
+        let mut open: Vec<bool> = maze
+            .cells
+            .iter()
+            .map(|cell| cell.active() && !rng.gen_bool(self.fill))
+            .collect();
+        for _ in 0..self.steps {
+            open = smooth(maze, &open);
+        }
+
+        // only the largest cave stays, so every cell of it can be reached.
+        // A grid too small to hold any after smoothing is left one open room
+        let cave = Mask::from_cells(maze.m, maze.n, open);
+        if cave.cells.contains(&true) {
+            for (cell, &open) in maze.cells.iter_mut().zip(&cave.cells) {
+                cell.set_active(open);
+            }
+        }
+        maze.clear_walls();
+    }
+}
+
+/// one round of smoothing. Masked out cells and the space around the
+/// grid count as rock, so caves are walled in
+fn smooth(maze: &Maze, open: &[bool]) -> Vec<bool> {
+    maze.cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| {
+            let p = Position {
+                f: 0,
+                r: i / maze.n,
+                c: i % maze.n,
+            };
+            let rocks = AROUND
+                .iter()
+                .filter(|&&dir| {
+                    p.apply_move(dir)
+                        .filter(|q| q.r < maze.m && q.c < maze.n)
+                        .is_none_or(|q| !open[maze.index(q)])
+                })
+                .count();
+            let limit = if open[i] { ERODE } else { KEEP };
+            cell.active() && rocks < limit
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::maze::Placement;
+    use crate::topology::Topology;
+
+    #[test]
+    fn goal_can_be_reached_from_start() {
+        for size in 1..16 {
+            for seed in 0..4 {
+                let mut maze = Maze::new(size, size + seed as usize);
+                Cave::default().generate(&mut maze, &mut MazeRng::seed_from_u64(seed));
+                maze.place_ends(Placement::Farthest);
+                assert!(maze.validate().is_ok(), "{}x{}", maze.m, maze.n);
+                assert!(maze.distances(maze.start)[maze.index(maze.goal)].is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn all_rock_leaves_one_open_room() {
+        let mut maze = Maze::new(3, 4);
+        let cave = Cave {
+            fill: 1.0,
+            steps: 4,
+        };
+        cave.generate(&mut maze, &mut MazeRng::seed_from_u64(1));
+        maze.place_ends(Placement::Farthest);
+        assert_eq!(maze.positions().count(), 12);
+        for p in maze.positions() {
+            assert!(maze.links(p).len() == maze.neighbors(p).len());
+        }
+        assert!(maze.distances(maze.start)[maze.index(maze.goal)].is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "square cells")]
+    fn only_plain_mazes_are_carved() {
+        let mut maze = Maze {
+            topology: Topology::Hex,
+            ..Maze::new(6, 6)
+        };
+        Cave::default().generate(&mut maze, &mut MazeRng::seed_from_u64(1));
+    }
+}
@@ -0,0 +1,144 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::{join_regions, MazeGenerator, MazeRng};
+use crate::maze::Maze;
+use crate::position::{Direction, Position};
+
+/// one row handed out by `EllerRows`: which of its cells are linked to
+/// the cell on their right, and which to the cell below them in the row
+/// before
+pub struct Row {
+    pub right: Vec<bool>,
+    pub down: Vec<bool>,
+}
+
+/// Eller's algorithm as an endless stream of rows, built from the bottom up.
+/// Only the set ids of the newest row are kept, so memory doesn't grow with
+/// the number of rows handed out
+pub struct EllerRows {
+    n: usize,
+    sets: Vec<usize>,
+    next_set: usize,
+}
+
+impl EllerRows {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            sets: vec![],
+            next_set: 0,
+        }
+    }
+
+    /// build the next row up
+    pub fn next_row(&mut self, rng: &mut MazeRng) -> Row {
+        let mut row = self.start_row(rng);
+
+        // randomly join neighbors that aren't connected yet
+        for c in 0..self.n - 1 {
+            if self.sets[c] != self.sets[c + 1] && rng.gen_bool(0.5) {
+                self.join(&mut row, c);
+            }
+        }
+        row
+    }
+
+    /// build a closing row that joins every set left, ending the maze
+    pub fn last_row(&mut self, rng: &mut MazeRng) -> Row {
+        let mut row = self.start_row(rng);
+        for c in 0..self.n - 1 {
+            if self.sets[c] != self.sets[c + 1] {
+                self.join(&mut row, c);
+            }
+        }
+        row
+    }
+
+    /// regroup the newest row so cells share a set exactly when they share
+    /// an entry in `components`
+    pub fn regroup(&mut self, components: &[usize]) {
+        let base = self.next_set;
+        for (set, &component) in self.sets.iter_mut().zip(components) {
+            *set = base + component;
+        }
+        self.next_set = base + components.iter().max().map_or(0, |&max| max + 1);
+    }
+
+    /// carry every set of the previous row up through at least one of its
+    /// cells and hand fresh sets to the cells nothing carried into
+    fn start_row(&mut self, rng: &mut MazeRng) -> Row {
+        let mut from_below = vec![false; self.n];
+        if !self.sets.is_empty() {
+            let mut columns: Vec<usize> = (0..self.n).collect();
+            columns.shuffle(rng);
+            let mut carried: Vec<usize> = vec![];
+            for c in columns {
+                if !carried.contains(&self.sets[c]) || rng.gen_bool(0.3) {
+                    carried.push(self.sets[c]);
+                    from_below[c] = true;
+                }
+            }
+        } else {
+            self.sets = vec![0; self.n];
+        }
+
+        for (c, &linked) in from_below.iter().enumerate() {
+            if !linked {
+                self.sets[c] = self.next_set;
+                self.next_set += 1;
+            }
+        }
+        Row {
+            right: vec![false; self.n],
+            down: from_below,
+        }
+    }
+
+    /// link column `c` to the one on its right and merge the two sets
+    fn join(&mut self, row: &mut Row, c: usize) {
+        row.right[c] = true;
+        let (keep, gone) = (self.sets[c], self.sets[c + 1]);
+        for set in self.sets.iter_mut().filter(|set| **set == gone) {
+            *set = keep;
+        }
+    }
+}
+
+/// Eller's algorithm filling a whole Maze, bottom row first
+pub struct Eller;
+
+impl MazeGenerator for Eller {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        // rows are unwrapped strips of square cells on a single floor,
+        // anything else gets a plain Kruskal
+        if !maze.is_plain() {
+            return join_regions(maze, rng);
+        }
+        let mut rows = EllerRows::new(maze.n);
+        for r in (0..maze.m).rev() {
+            let row = if r == 0 {
+                rows.last_row(rng)
+            } else {
+                rows.next_row(rng)
+            };
+            for c in 0..maze.n {
+                let p = Position { f: 0, r, c };
+                for (dir, linked) in [
+                    (Direction::Right, row.right[c]),
+                    (Direction::Down, row.down[c]),
+                ] {
+                    if linked && maze.neighbor(p, dir).is_some() {
+                        maze.carve(p, dir);
+                    }
+                }
+            }
+        }
+
+        // rows don't know about masks, so links that ran through masked out
+        // cells get patched up with extra walls knocked down
+        if maze.positions().count() < maze.m * maze.n {
+            join_regions(maze, rng);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::{below, MazeGenerator, MazeRng};
+use crate::maze::Maze;
+
+/// growing tree: keep a list of active cells and carve out from one of them
+/// each step, dropping cells once they have nowhere left to go. Always
+/// picking the newest cell gives recursive backtracker mazes, picking at
+/// random gives Prim style ones and picking the oldest gives long runs
+pub struct GrowingTree {
+    /// chance of growing from the newest active cell
+    pub newest: f64,
+    /// chance of growing from the oldest active cell, any other pick is random
+    pub oldest: f64,
+}
+
+impl GrowingTree {
+    /// sweep from Prim style (0.0) to recursive backtracker style (1.0)
+    pub fn with_bias(bias: f64) -> Self {
+        Self {
+            newest: bias.clamp(0.0, 1.0),
+            oldest: 0.0,
+        }
+    }
+}
+
+impl Default for GrowingTree {
+    fn default() -> Self {
+        Self::with_bias(0.5)
+    }
+}
+
+impl MazeGenerator for GrowingTree {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        let mut visited = vec![false; maze.cells.len()];
+        let start = maze.random_position(rng);
+        visited[maze.index(start)] = true;
+        let mut active = vec![start];
+
+        while !active.is_empty() {
+            let roll: f64 = rng.gen();
+            let i = if roll < self.newest {
+                active.len() - 1
+            } else if roll < self.newest + self.oldest {
+                0
+            } else {
+                below(rng, active.len())
+            };
+
+            let p = active[i];
+            let dirs: Vec<_> = maze
+                .neighbors(p)
+                .into_iter()
+                .filter(|(_, new_pos)| !visited[maze.index(*new_pos)])
+                .map(|(dir, _)| dir)
+                .collect();
+            match dirs.choose(rng) {
+                Some(&dir) => {
+                    let new_pos = maze.carve(p, dir);
+                    visited[maze.index(new_pos)] = true;
+                    active.push(new_pos);
+                }
+                // keep the list in age order for newest/oldest picks
+                None => {
+                    active.remove(i);
+                }
+            }
+        }
+    }
+}
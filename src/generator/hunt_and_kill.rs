@@ -0,0 +1,212 @@
+use rand::seq::SliceRandom;
+
+use super::{MazeGenerator, MazeRng};
+use crate::maze::Maze;
+use crate::position::Direction;
+
+/// randomized walk that carves until it gets stuck or walks `max_depth`
+/// cells, then hunts for an unvisited cell next to the carved region
+/// and starts walking again from there
+pub struct HuntAndKill {
+    pub max_depth: usize,
+}
+
+impl Default for HuntAndKill {
+    fn default() -> Self {
+        Self {
+            max_depth: usize::MAX,
+        }
+    }
+}
+
+/// the active neighbors of a cell, by direction and index
+type Near = [(Direction, usize); Direction::COUNT];
+
+impl MazeGenerator for HuntAndKill {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        if maze.is_plain() && !maze.is_woven() {
+            self.hunt(maze, rng, plain_neighbors, plain_carve);
+        } else {
+            self.hunt(maze, rng, neighbors, |maze, i, dir| {
+                maze.carve(maze.position(i), dir);
+            });
+        }
+    }
+}
+
+impl HuntAndKill {
+    /// walk and hunt over the cells by index, finding the neighbors of a
+    /// cell and carving between two of them with the functions given
+    fn hunt(
+        &self,
+        maze: &mut Maze,
+        rng: &mut MazeRng,
+        neighbors: impl Fn(&Maze, usize, &mut Near) -> usize,
+        carve: impl Fn(&mut Maze, usize, Direction),
+    ) {
+        let len = maze.cells.len();
+        let Some(mut i) = maze.cells.iter().position(|cell| cell.active()) else {
+            return;
+        };
+        let mut visited = vec![false; len];
+
+        // unvisited cells next to the visited region, so a hunt doesn't
+        // have to scan the grid. The hunt takes the first one in the grid,
+        // and every cell before it has been visited unless the Maze has
+        // cells masked out, so a cursor moving down the grid finds it. It
+        // only steps back for a cell queued behind it
+        let mut queued = vec![false; len];
+        let mut cursor = len;
+
+        let mut near = [(Direction::Left, 0); Direction::COUNT];
+        let mut depth_count = 0;
+        loop {
+            let fresh = !visited[i];
+            visited[i] = true;
+
+            // if starting a new section, make sure to connect something
+            // visited, otherwise walk on into the unvisited
+            let mut count = 0;
+            for k in 0..neighbors(maze, i, &mut near) {
+                let j = near[k].1;
+                if fresh && !visited[j] && !queued[j] {
+                    queued[j] = true;
+                    cursor = usize::min(cursor, j);
+                }
+                if visited[j] == (depth_count == 0) {
+                    near.swap(count, k);
+                    count += 1;
+                }
+            }
+
+            // a walk that's gone `max_depth` cells stops before carving into
+            // another, which would leave it open to a second passage later
+            let walked = if depth_count <= self.max_depth {
+                near[..count].choose(rng).copied()
+            } else {
+                None
+            };
+
+            match walked {
+                Some((dir, j)) => {
+                    carve(maze, i, dir);
+                    i = j;
+                    depth_count += 1;
+                }
+                None => {
+                    // hunt for an unvisited cell touching the visited region
+                    depth_count = 0;
+                    while cursor < len && (visited[cursor] || !queued[cursor]) {
+                        cursor += 1;
+                    }
+                    if cursor == len {
+                        break;
+                    }
+                    i = cursor;
+                }
+            }
+        }
+    }
+}
+
+/// the active neighbors of cell `i`, in the order `Maze::directions` lists
+/// them, returning how many there are
+fn neighbors(maze: &Maze, i: usize, near: &mut Near) -> usize {
+    let p = maze.position(i);
+    let mut count = 0;
+    for dir in maze.directions() {
+        if let Some(q) = maze.neighbor(p, dir) {
+            near[count] = (dir, maze.index(q));
+            count += 1;
+        }
+    }
+    count
+}
+
+/// `neighbors` for a plain Maze, whose neighbors are a step along the row
+/// or a row's length away
+fn plain_neighbors(maze: &Maze, i: usize, near: &mut Near) -> usize {
+    let (n, len) = (maze.n, maze.cells.len());
+    let sides = [
+        (Direction::Up, i >= n, i.wrapping_sub(n)),
+        (Direction::Right, i % n + 1 < n, i + 1),
+        (Direction::Down, i + n < len, i + n),
+        (Direction::Left, !i.is_multiple_of(n), i.wrapping_sub(1)),
+    ];
+    let mut count = 0;
+    for (dir, on_grid, j) in sides {
+        if on_grid && maze.cells[j].active() {
+            near[count] = (dir, j);
+            count += 1;
+        }
+    }
+    count
+}
+
+/// `Maze::carve` for a plain Maze, opening the wall straight in the bitset
+/// of the cell that keeps it, which is the one below or right of it
+fn plain_carve(maze: &mut Maze, i: usize, dir: Direction) {
+    let len = maze.cells.len();
+    let (side, j) = match dir {
+        Direction::Right => (Direction::Left, i + 1),
+        Direction::Down => (Direction::Up, i + maze.n),
+        _ => (dir, i),
+    };
+    maze.walls.set_open(side, j, true, len);
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::mask::Mask;
+    use crate::maze::validate::Layout;
+
+    #[test]
+    fn plain_path_carves_like_the_general_one() {
+        for (seed, blank) in [
+            Maze::new(1, 1),
+            Maze::new(1, 9),
+            Maze::new(9, 1),
+            Maze::new(17, 23),
+            Maze::with_mask(&Mask::heart(20, 20)),
+            Maze::with_mask(&Mask::from_ascii("XX.XX\nX...X\nXXXXX")),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            assert!(blank.is_plain());
+            for max_depth in [usize::MAX, 4] {
+                let generator = HuntAndKill { max_depth };
+                let mut plain = blank.clone();
+                let mut rng = MazeRng::seed_from_u64(seed as u64);
+                generator.hunt(&mut plain, &mut rng, plain_neighbors, plain_carve);
+
+                let mut general = blank.clone();
+                let mut rng = MazeRng::seed_from_u64(seed as u64);
+                generator.hunt(&mut general, &mut rng, neighbors, |maze, i, dir| {
+                    maze.carve(maze.position(i), dir);
+                });
+                assert_eq!(plain, general, "maze {} at depth {}", seed, max_depth);
+            }
+        }
+    }
+
+    #[test]
+    fn depth_limited_walks_carve_perfect_mazes() {
+        for max_depth in [0, 1, 3, 10] {
+            for seed in 0..50 {
+                let mut maze = Maze::new(10, 10);
+                HuntAndKill { max_depth }.generate(&mut maze, &mut MazeRng::seed_from_u64(seed));
+                assert_eq!(
+                    maze.validate(),
+                    Ok(Layout::Perfect),
+                    "depth {}, seed {}",
+                    max_depth,
+                    seed
+                );
+            }
+        }
+    }
+}
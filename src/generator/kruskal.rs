@@ -0,0 +1,75 @@
+use rand::seq::SliceRandom;
+
+use super::{MazeGenerator, MazeRng};
+use crate::cell::Wall;
+use crate::maze::Maze;
+
+/// union-find over cell indices
+pub struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    pub fn new(size: usize) -> Self {
+        Self {
+            parent: (0..size).collect(),
+        }
+    }
+
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// merge the sets holding `a` and `b`, returning false if they were already one set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        self.parent[b] = a;
+        true
+    }
+}
+
+/// randomized Kruskal: knock down walls in random order whenever
+/// they separate two regions that aren't connected yet
+pub struct Kruskal;
+
+impl MazeGenerator for Kruskal {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        join_regions(maze, rng);
+    }
+}
+
+/// knock down walls in random order wherever they separate cells that can't
+/// reach each other yet, until the whole Maze is one region
+pub fn join_regions(maze: &mut Maze, rng: &mut MazeRng) {
+    let mut sets = DisjointSet::new(maze.cells.len());
+
+    // every interior wall once, named by the cell that comes first
+    let mut walls = vec![];
+    for p in maze.positions() {
+        for (dir, new_pos) in maze.neighbors(p) {
+            if maze.index(new_pos) < maze.index(p) {
+                continue;
+            }
+            if maze.wall(p, dir) == Wall::No {
+                sets.union(maze.index(p), maze.index(new_pos));
+            } else {
+                walls.push((p, dir));
+            }
+        }
+    }
+    walls.shuffle(rng);
+
+    for (p, dir) in walls {
+        let new_pos = maze.neighbor(p, dir).unwrap();
+        if sets.union(maze.index(p), maze.index(new_pos)) {
+            maze.carve(p, dir);
+        }
+    }
+}
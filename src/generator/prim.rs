@@ -0,0 +1,43 @@
+use rand::seq::SliceRandom;
+
+use super::{below, MazeGenerator, MazeRng};
+use crate::maze::Maze;
+use crate::position::Position;
+
+/// randomized Prim: grow a single region by attaching a random
+/// frontier cell to a random neighbor already in the region
+pub struct Prim;
+
+impl MazeGenerator for Prim {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        let mut in_maze = vec![false; maze.cells.len()];
+        let mut in_frontier = vec![false; maze.cells.len()];
+        let mut frontier: Vec<Position> = vec![];
+
+        let mut added = Some(maze.random_position(rng));
+        while let Some(p) = added {
+            in_maze[maze.index(p)] = true;
+            for (_, new_pos) in maze.neighbors(p) {
+                let i = maze.index(new_pos);
+                if !in_maze[i] && !in_frontier[i] {
+                    in_frontier[i] = true;
+                    frontier.push(new_pos);
+                }
+            }
+
+            if frontier.is_empty() {
+                added = None;
+            } else {
+                let p = frontier.swap_remove(below(rng, frontier.len()));
+                let dirs: Vec<_> = maze
+                    .neighbors(p)
+                    .into_iter()
+                    .filter(|(_, new_pos)| in_maze[maze.index(*new_pos)])
+                    .map(|(dir, _)| dir)
+                    .collect();
+                maze.carve(p, *dirs.choose(rng).unwrap());
+                added = Some(p);
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use rand::Rng;
+
+use super::{below, join_regions, MazeGenerator, MazeRng};
+use crate::maze::Maze;
+use crate::position::{Direction, Position};
+
+/// recursive division: start from an open grid and keep splitting
+/// regions with a wall that has a single gap in it
+pub struct RecursiveDivision;
+
+impl MazeGenerator for RecursiveDivision {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        // straight dividing walls only split single floor square grids with
+        // edges that don't wrap, anything else gets a plain Kruskal
+        if !maze.is_plain() {
+            return join_regions(maze, rng);
+        }
+        maze.clear_walls();
+
+        // regions still to divide as (top, left, height, width)
+        let mut regions = vec![(0, 0, maze.m, maze.n)];
+        while let Some((top, left, height, width)) = regions.pop() {
+            if height < 2 || width < 2 {
+                continue;
+            }
+
+            // split across the longer side so corridors run along it
+            let horizontal = if height == width {
+                rng.gen_bool(0.5)
+            } else {
+                height > width
+            };
+
+            if horizontal {
+                // wall under row `split`
+                let split = top + below(rng, height - 1);
+                let line: Vec<_> = (left..left + width)
+                    .map(|c| Position { f: 0, r: split, c })
+                    .collect();
+                build_line(maze, &line, Direction::Down, rng);
+                regions.push((top, left, split - top + 1, width));
+                regions.push((split + 1, left, top + height - split - 1, width));
+            } else {
+                // wall right of column `split`
+                let split = left + below(rng, width - 1);
+                let line: Vec<_> = (top..top + height)
+                    .map(|r| Position { f: 0, r, c: split })
+                    .collect();
+                build_line(maze, &line, Direction::Right, rng);
+                regions.push((top, left, height, split - left + 1));
+                regions.push((top, split + 1, height, left + width - split - 1));
+            }
+        }
+    }
+}
+
+/// wall off the `dir` side of a line of cells, leaving a gap in every run of
+/// cells that can be crossed. On a full grid that's a single gap, on a masked
+/// one it keeps pieces of the shape that only meet across this line linked
+fn build_line(maze: &mut Maze, line: &[Position], dir: Direction, rng: &mut MazeRng) {
+    let crossable = |p: &Position| maze.neighbor(*p, dir).is_some();
+    let runs: Vec<Vec<Position>> = line
+        .split(|p| !crossable(p))
+        .filter(|run| !run.is_empty())
+        .map(|run| run.to_vec())
+        .collect();
+    for run in runs {
+        let gap = below(rng, run.len());
+        for (i, &p) in run.iter().enumerate() {
+            if i != gap {
+                maze.build_wall(p, dir);
+            }
+        }
+    }
+}
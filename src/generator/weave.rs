@@ -0,0 +1,92 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::{join_regions, DisjointSet, MazeGenerator, MazeRng};
+use crate::cell::Wall;
+use crate::maze::Maze;
+use crate::position::{Axis, Direction, Position};
+use crate::topology::Topology;
+
+/// weave: lay down crossings where one corridor tunnels under another,
+/// then join everything up Kruskal style around them
+pub struct Weave {
+    /// fraction of cells tried as crossings
+    pub density: f64,
+}
+
+impl Default for Weave {
+    fn default() -> Self {
+        Self { density: 0.3 }
+    }
+}
+
+impl MazeGenerator for Weave {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        // tunnels run straight along an axis, which only square cells have
+        if maze.topology == Topology::Square {
+            add_crossings(maze, self.density, rng);
+        }
+        join_regions(maze, rng);
+    }
+}
+
+/// turn up to `density` of the cells into crossings, each one a corridor
+/// over the cell with a passage beneath it linking the cells on the other
+/// axis. A crossing needs all four neighbors untouched so tunnels never run
+/// into each other, and may not close a loop
+fn add_crossings(maze: &mut Maze, density: f64, rng: &mut MazeRng) {
+    let mut sets = DisjointSet::new(maze.cells.len());
+    let mut candidates: Vec<Position> = maze.positions().collect();
+    candidates.shuffle(rng);
+    let count = (density.clamp(0.0, 1.0) * candidates.len() as f64).round() as usize;
+
+    for &p in &candidates[..count] {
+        let under = if rng.gen() {
+            Axis::Vertical
+        } else {
+            Axis::Horizontal
+        };
+        let (over, across) = match under {
+            Axis::Vertical => (
+                [Direction::Left, Direction::Right],
+                [Direction::Up, Direction::Down],
+            ),
+            Axis::Horizontal => (
+                [Direction::Up, Direction::Down],
+                [Direction::Left, Direction::Right],
+            ),
+        };
+        let untouched = maze
+            .topology
+            .directions()
+            .iter()
+            .all(|&dir| maze.wall(p, dir) == Wall::Yes);
+        let around: Vec<Position> = over
+            .iter()
+            .chain(&across)
+            .filter_map(|&dir| maze.neighbor(p, dir))
+            .filter(|q| maze.cell(*q).under().is_none())
+            .collect();
+        if !untouched || around.len() != 4 {
+            continue;
+        }
+
+        // an untouched cell is a region of its own, so the corridor and
+        // the passage just have to join two different regions each
+        let [a, b, c, d] = [around[0], around[1], around[2], around[3]].map(|q| maze.index(q));
+        if sets.find(a) == sets.find(b) || sets.find(c) == sets.find(d) {
+            continue;
+        }
+        sets.union(a, maze.index(p));
+        sets.union(b, maze.index(p));
+        sets.union(c, d);
+
+        for dir in over {
+            maze.carve(p, dir);
+        }
+        maze.cell_mut(p).set_under(Some(under));
+        // from one side of the crossing, on under it to the other. The
+        // walls it goes through are the crossing's own, which opens them
+        maze.carve(around[2], across[1]);
+    }
+}
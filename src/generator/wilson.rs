@@ -0,0 +1,37 @@
+use rand::seq::SliceRandom;
+
+use super::{MazeGenerator, MazeRng};
+use crate::maze::Maze;
+use crate::position::Direction;
+
+/// Wilson's algorithm: loop-erased random walks from each cell outside
+/// the maze until they hit it, giving an unbiased spanning tree
+pub struct Wilson;
+
+impl MazeGenerator for Wilson {
+    fn generate(&self, maze: &mut Maze, rng: &mut MazeRng) {
+        let mut in_maze = vec![false; maze.cells.len()];
+        // direction each cell was last left by during the current walk
+        let mut exits: Vec<Option<Direction>> = vec![None; maze.cells.len()];
+
+        let first = maze.random_position(rng);
+        in_maze[maze.index(first)] = true;
+        for start in maze.positions().collect::<Vec<_>>() {
+            // walk until we touch the maze, overwriting exits erases loops
+            let mut p = start;
+            while !in_maze[maze.index(p)] {
+                let neighbors = maze.neighbors(p);
+                let (dir, new_pos) = *neighbors.choose(rng).unwrap();
+                exits[maze.index(p)] = Some(dir);
+                p = new_pos;
+            }
+
+            // retrace the loop-erased walk and carve it in
+            let mut p = start;
+            while !in_maze[maze.index(p)] {
+                in_maze[maze.index(p)] = true;
+                p = maze.carve(p, exits[maze.index(p)].unwrap());
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! mazes, their generators and solvers, and the games played on them.
+//! The app in `main.rs` puts them on screen and the benches time them
+
+// yew 0.19's html! macro expands component props into bare statements
+#![allow(clippy::unnecessary_operation)]
+
+pub mod cell;
+pub mod climb;
+pub mod demo;
+pub mod game;
+pub mod generator;
+pub mod mask;
+pub mod maze;
+pub mod position;
+#[cfg(feature = "serde")]
+pub mod save;
+pub mod solver;
+pub mod topology;
@@ -1,28 +1,68 @@
+// yew 0.19's html! macro expands component props into bare statements
+#![allow(clippy::unnecessary_operation)]
+
 use gloo_timers::callback::Interval;
 use std::collections::HashSet;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
-mod cell;
-mod game;
-mod maze;
-mod position;
+use maze_climb::cell::Terrain;
+use maze_climb::maze::analysis::Analysis;
+use maze_climb::position::Position;
+use maze_climb::topology::Topology;
+use maze_climb::{climb, demo, game, maze, solver};
+
+/// size of the window scrolling up the endless climb
+const CLIMB_ROWS: usize = 8;
+const CLIMB_COLS: usize = 7;
+
+/// seconds taken off the clock for a hint, and cells each hint shows
+const HINT_COST: usize = 5;
+const HINT_CELLS: usize = 3;
 
-use position::Position;
+/// milliseconds between the cells the demo shows
+const DEMO_TICK: u32 = 50;
+
+/// what the board is being played as
+enum Mode {
+    /// discrete levels handed out by the Game
+    Levels,
+    /// one endless maze that scrolls upward
+    Climb(Box<climb::Climb>),
+    /// solvers taking turns to find their way through mazes, on the
+    /// title screen
+    Demo(Box<demo::Demo>),
+}
 
 pub enum Msg {
-    ClickedCell { pos: (usize, usize) },
-    NewGame,
+    ClickedCell { pos: Position },
+    Floor(usize),
+    NewGame(Topology),
+    NewClimb,
+    NewDemo,
+    PlaySeed(u64),
+    Hint,
     Tick,
+    DemoTick,
     Reset,
 }
 
 pub struct App {
     game: game::Game,
+    mode: Mode,
     maze: maze::Maze,
     path: HashSet<Position>,
+    /// floor of the maze on show
+    floor: usize,
+    /// difficulty rating of the level's maze, worked out once per maze
+    stars: usize,
+    /// most the level's path may cost, worked out once per maze
+    cost_limit: Option<usize>,
     lost: bool,
     timer: usize,
     _interval: Interval,
+    /// faster ticks stepping the demo along, only running during the demo
+    demo_interval: Option<Interval>,
 }
 
 impl Component for App {
@@ -30,8 +70,9 @@ impl Component for App {
     type Properties = ();
 
     fn create(ctx: &Context<Self>) -> Self {
-        let game = game::Game::new();
-        let maze = game.get_maze();
+        // open on the demo until a game is picked
+        let game = game::Game::new(Topology::Square);
+        let (demo, maze) = demo::Demo::new(0, game.seed);
 
         let path: HashSet<Position> = HashSet::new();
 
@@ -39,65 +80,138 @@ impl Component for App {
         let interval = Interval::new(1_000, move || timer_callback.emit(()));
 
         Self {
-            game: game,
-            maze: maze,
-            path: path,
+            game,
+            mode: Mode::Demo(Box::new(demo)),
+            maze,
+            path,
+            floor: 0,
+            stars: 0,
+            cost_limit: None,
             lost: false,
             timer: 15,
             _interval: interval,
+            demo_interval: Some(demo_interval(ctx)),
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Msg::ClickedCell { pos: (a, b) } => {
+            // the demo plays itself
+            Msg::ClickedCell { .. } if matches!(self.mode, Mode::Demo(_)) => false,
+            Msg::ClickedCell { pos } => {
                 // flip a cell
-                self.maze.cells[a * self.maze.n + b].clicked =
-                    !self.maze.cells[a * self.maze.n + b].clicked;
+                let cell = self.maze.cell_mut(pos);
+                cell.set_clicked(!cell.clicked());
 
                 // if flipped to clicked add to path
-                if self.maze.cells[a * self.maze.n + b].clicked {
-                    self.path.insert(Position { r: a, c: b });
+                if cell.clicked() {
+                    self.path.insert(pos);
                 } else {
-                    self.path.remove(&Position { r: a, c: b });
+                    self.path.remove(&pos);
                 }
 
-                // check current path and see if complete
-                if self.path.contains(&position::Position { r: 0, c: 0 }) {
-                    if self.maze.is_connected(
-                        position::Position { r: 0, c: 0 },
-                        position::Position {
-                            r: self.maze.m - 1,
-                            c: self.maze.n - 1,
-                        },
-                        &self.path,
-                    ) {
-                        self.game.apply_win();
-                        self.maze = self.game.get_maze();
-                        self.path = HashSet::new();
-                        self.timer = 15;
+                match &mut self.mode {
+                    // climbing higher buys more time
+                    Mode::Climb(climb) => {
+                        if climb.advance(&mut self.maze, &mut self.path) {
+                            self.timer = 15;
+                        }
+                    }
+                    // check current path and see if complete, which needs
+                    // the goal on the top floor
+                    Mode::Levels => {
+                        if game::Game::path_wins(&self.maze, &self.path, self.cost_limit) {
+                            self.game.apply_win();
+                            self.new_maze();
+                            self.timer = 15;
+                        }
                     }
+                    Mode::Demo(_) => {}
                 }
                 true
             }
+            Msg::Floor(floor) => {
+                self.floor = floor;
+                true
+            }
             Msg::Reset => {
-                if self.game.lives > 1 {
+                if self.game.lives > 1 && !matches!(self.mode, Mode::Demo(_)) {
                     self.game.apply_loss();
-                    self.maze = self.game.get_maze();
-                    self.path = HashSet::new();
+                    self.new_maze();
                     true
                 } else {
                     false
                 }
             }
-            Msg::NewGame => {
-                self.game = game::Game::new();
-                self.maze = self.game.get_maze();
+            Msg::NewGame(topology) => {
+                self.game = game::Game::new(topology);
+                self.mode = Mode::Levels;
+                self.demo_interval = None;
+                self.new_maze();
+                self.lost = false;
+                self.timer = 15;
+                true
+            }
+            Msg::NewClimb => {
+                // the climb streams square rows
+                self.game = game::Game::new(Topology::Square);
+                let (climb, maze) =
+                    climb::Climb::new(CLIMB_ROWS, CLIMB_COLS, self.game.maze_seed());
+                self.mode = Mode::Climb(Box::new(climb));
+                self.demo_interval = None;
+                self.maze = maze;
                 self.path = HashSet::new();
+                self.floor = 0;
                 self.lost = false;
                 self.timer = 15;
                 true
             }
+            Msg::NewDemo => {
+                self.game = game::Game::new(Topology::Square);
+                let (demo, maze) = demo::Demo::new(0, self.game.seed);
+                self.mode = Mode::Demo(Box::new(demo));
+                self.demo_interval = Some(demo_interval(ctx));
+                self.maze = maze;
+                self.path = HashSet::new();
+                self.floor = 0;
+                self.lost = false;
+                true
+            }
+            Msg::PlaySeed(seed) => {
+                // replay the current mode from a given seed
+                self.game = game::Game::with_seed(seed, self.game.topology);
+                self.new_maze();
+                self.lost = false;
+                self.timer = 15;
+                true
+            }
+            Msg::Hint => {
+                // the climb has no goal to point the way to
+                if self.timer < HINT_COST || !matches!(self.mode, Mode::Levels) {
+                    return false;
+                }
+                self.timer -= HINT_COST;
+                let hint = solver::hint(&self.maze, HINT_CELLS);
+                self.maze.show_hint(&hint);
+                true
+            }
+            Msg::DemoTick => {
+                let Mode::Demo(demo) = &mut self.mode else {
+                    return false;
+                };
+                if demo.over() {
+                    let (next, maze) = demo::Demo::new(demo.round + 1, self.game.seed);
+                    **demo = next;
+                    self.maze = maze;
+                    self.floor = 0;
+                } else if let Some(p) = demo.advance(&mut self.maze) {
+                    // follow the solver up and down the stairs
+                    self.floor = p.f;
+                }
+                true
+            }
+            // the demo has no clock
+            Msg::Tick if matches!(self.mode, Mode::Demo(_)) => false,
             Msg::Tick => {
                 if self.timer > 0 {
                     self.timer -= 1;
@@ -121,23 +235,57 @@ impl Component for App {
     fn view(&self, ctx: &Context<Self>) -> Html {
         // define callbacks
         let reset_maze = ctx.link().callback(|_| Msg::Reset);
-        let clicked_cell = ctx
-            .link()
-            .callback(|(a, b)| Msg::ClickedCell { pos: (a, b) });
+        let clicked_cell = ctx.link().callback(|pos| Msg::ClickedCell { pos });
+        let show_floor = ctx.link().callback(Msg::Floor);
+        let play_seed = ctx.link().batch_callback(|e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            input.value().trim().parse().ok().map(Msg::PlaySeed)
+        });
 
         html! {
             <>
-                <button onclick={ctx.link().callback(|_| Msg::NewGame)}>{ "New Game" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::NewGame(Topology::Square))}>{ "New Game" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::NewGame(Topology::Hex))}>{ "Hex Game" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::NewGame(Topology::Polar))}>{ "Round Game" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::NewClimb)}>{ "Endless Climb" }</button>
+                <button onclick={ctx.link().callback(|_| Msg::NewDemo)}>{ "Demo" }</button>
+                <p>
+                    { "Seed: " } { self.game.seed } { " " }
+                    <input placeholder="play a seed" onchange={ play_seed } />
+                </p>
                 if self.lost{
                     <p> { "You lost!" } </p>
                 } else{
-                    <p> { "Level: " } { self.game.level } </p>
-                    <p> { "Lives: " } { self.game.lives } </p>
-                    <p> { "Time: " } { self.timer } </p>
+                    if let Mode::Demo(demo) = &self.mode {
+                        <p> { "Solver: " } { demo.solver } </p>
+                        <p>
+                            { "Explored " } { demo.solution.explored() } { " cells in " }
+                            { demo.solution.steps } { " steps, path of " } { demo.solution.path.len() }
+                        </p>
+                    } else {
+                        if let Mode::Climb(climb) = &self.mode {
+                            <p> { "Height: " } { climb.height } </p>
+                        } else {
+                            <p> { "Level: " } { self.game.level } </p>
+                            <p> { "Difficulty: " } { difficulty(self.stars) } </p>
+                            if let Some(limit) = self.cost_limit {
+                                <p> { "Cost limit: " } { limit } { " (" } { terrain_costs() } { ")" } </p>
+                                <p> { "Path cost: " } { game::Game::path_cost(&self.maze, &self.path) } </p>
+                            }
+                            <button onclick={ctx.link().callback(|_| Msg::Hint)} disabled={ self.timer < HINT_COST }>
+                                { format!("Hint (-{}s)", HINT_COST) }
+                            </button>
+                        }
+                        <p> { "Lives: " } { self.game.lives } </p>
+                        <p> { "Time: " } { self.timer } </p>
+                    }
                     <maze::MazeView
                         maze={ self.maze.clone() }
+                        floor={ self.floor }
+                        heatmap={ matches!(self.mode, Mode::Demo(_)) }
                         click_callback={ clicked_cell }
                         reset_callback={ reset_maze }
+                        floor_callback={ show_floor }
                     />
                 }
             </>
@@ -145,6 +293,53 @@ impl Component for App {
     }
 }
 
+impl App {
+    /// swap in a fresh maze for the current mode and clear the path
+    fn new_maze(&mut self) {
+        match &mut self.mode {
+            Mode::Levels => {
+                self.maze = self.game.get_maze();
+                self.stars = Analysis::of(&self.maze).stars();
+                self.cost_limit = self.game.cost_limit(&self.maze);
+            }
+            Mode::Climb(climb) => {
+                let (fresh, maze) =
+                    climb::Climb::new(CLIMB_ROWS, CLIMB_COLS, self.game.maze_seed());
+                **climb = fresh;
+                self.maze = maze;
+            }
+            Mode::Demo(demo) => {
+                let (fresh, maze) = demo::Demo::new(demo.round, self.game.seed);
+                **demo = fresh;
+                self.maze = maze;
+            }
+        }
+        self.path = HashSet::new();
+        self.floor = 0;
+    }
+}
+
+/// interval stepping the demo along
+fn demo_interval(ctx: &Context<App>) -> Interval {
+    let demo_callback = ctx.link().callback(|_| Msg::DemoTick);
+    Interval::new(DEMO_TICK, move || demo_callback.emit(()))
+}
+
+/// a difficulty rating as a row of stars
+fn difficulty(stars: usize) -> String {
+    "★".repeat(stars) + &"☆".repeat(5 - stars)
+}
+
+/// what stepping onto each rough terrain costs, next to a cost of 1 for
+/// bare floor
+fn terrain_costs() -> String {
+    Terrain::ROUGH
+        .iter()
+        .map(|terrain| format!("{:?} {}", terrain, terrain.cost()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
     yew::start_app::<App>();
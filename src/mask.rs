@@ -0,0 +1,160 @@
+use crate::position::Position;
+use crate::topology::Topology;
+
+/// which cells of an m×n grid belong to a Maze's shape
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mask {
+    pub m: usize,
+    pub n: usize,
+    pub cells: Vec<bool>,
+}
+
+impl Mask {
+    /// a mask from ASCII art, one line per row. Spaces and dots are left
+    /// out of the shape, any other character is part of it
+    pub fn from_ascii(template: &str) -> Self {
+        let lines: Vec<&str> = template.lines().filter(|l| !l.trim().is_empty()).collect();
+        let n = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut cells = vec![false; lines.len() * n];
+        for (r, line) in lines.iter().enumerate() {
+            for (c, ch) in line.chars().enumerate() {
+                cells[r * n + c] = ch != ' ' && ch != '.';
+            }
+        }
+        Self::from_cells(lines.len(), n, cells)
+    }
+
+    /// the largest ellipse fitting an m×n grid
+    pub fn circle(m: usize, n: usize) -> Self {
+        Self::from_fn(m, n, |x, y| x * x + y * y <= 1.0)
+    }
+
+    /// a heart filling an m×n grid
+    pub fn heart(m: usize, n: usize) -> Self {
+        Self::from_fn(m, n, |x, y| {
+            // classic heart curve, nudged so it fills the grid's box
+            let (x, y) = (1.15 * x, 1.15 * -y + 0.1);
+            (x * x + y * y - 1.0).powi(3) - x * x * y.powi(3) <= 0.0
+        })
+    }
+
+    /// a mask of every cell whose center, scaled to [-1, 1] on both axes,
+    /// falls inside `shape`
+    fn from_fn(m: usize, n: usize, shape: impl Fn(f32, f32) -> bool) -> Self {
+        let scale = |i: usize, len: usize| 2.0 * (i as f32 + 0.5) / len as f32 - 1.0;
+        let cells = (0..m * n)
+            .map(|i| shape(scale(i % n, n), scale(i / n, m)))
+            .collect();
+        Self::from_cells(m, n, cells)
+    }
+
+    /// keep only the largest connected region so every cell can be reached
+    pub fn from_cells(m: usize, n: usize, mut cells: Vec<bool>) -> Self {
+        let mut region = vec![usize::MAX; m * n];
+        let mut best = (0, 0);
+        for i in 0..m * n {
+            if !cells[i] || region[i] != usize::MAX {
+                continue;
+            }
+            // flood fill this region
+            let mut size = 0;
+            let mut stack = vec![i];
+            region[i] = i;
+            while let Some(j) = stack.pop() {
+                size += 1;
+                let p = Position {
+                    f: 0,
+                    r: j / n,
+                    c: j % n,
+                };
+                for &dir in Topology::Square.directions() {
+                    if let Some(q) = p.apply_move(dir).filter(|q| q.r < m && q.c < n) {
+                        let k = q.r * n + q.c;
+                        if cells[k] && region[k] == usize::MAX {
+                            region[k] = i;
+                            stack.push(k);
+                        }
+                    }
+                }
+            }
+            if size > best.1 {
+                best = (i, size);
+            }
+        }
+        for (cell, &r) in cells.iter_mut().zip(&region) {
+            *cell = r == best.0 && *cell;
+        }
+        Mask { m, n, cells }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// number of separate regions of cells in the mask, going between
+    /// cells across their sides
+    fn regions(mask: &Mask) -> usize {
+        let mut seen = vec![false; mask.cells.len()];
+        let mut regions = 0;
+        for i in 0..mask.cells.len() {
+            if !mask.cells[i] || seen[i] {
+                continue;
+            }
+            regions += 1;
+            seen[i] = true;
+            let mut stack = vec![i];
+            while let Some(j) = stack.pop() {
+                let (r, c) = (j / mask.n, j % mask.n);
+                let sides = [
+                    (r > 0, j.wrapping_sub(mask.n)),
+                    (r + 1 < mask.m, j + mask.n),
+                    (c > 0, j.wrapping_sub(1)),
+                    (c + 1 < mask.n, j + 1),
+                ];
+                for (on_grid, k) in sides {
+                    if on_grid && mask.cells[k] && !seen[k] {
+                        seen[k] = true;
+                        stack.push(k);
+                    }
+                }
+            }
+        }
+        regions
+    }
+
+    #[test]
+    fn shapes_are_one_region() {
+        let mut masks = vec![
+            Mask::from_ascii("XX.XX\nX...X\nXXXXX"),
+            Mask::from_ascii("X.X\n...\nX.X"),
+        ];
+        for size in 3..16 {
+            masks.push(Mask::circle(size, size));
+            masks.push(Mask::circle(size, 2 * size));
+            masks.push(Mask::heart(size, size));
+        }
+        for mask in masks {
+            assert_eq!(regions(&mask), 1, "{:?}", mask);
+        }
+    }
+
+    #[test]
+    fn from_cells_keeps_the_largest_region() {
+        let (x, o) = (true, false);
+        #[rustfmt::skip]
+        let cells = vec![
+            x, x, o, x,
+            o, o, o, x,
+            x, o, x, x,
+        ];
+        let mask = Mask::from_cells(3, 4, cells);
+        #[rustfmt::skip]
+        assert_eq!(mask.cells, vec![
+            o, o, o, x,
+            o, o, o, x,
+            o, o, x, x,
+        ]);
+        assert_eq!(regions(&mask), 1);
+    }
+}
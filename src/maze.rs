@@ -1,270 +1,936 @@
-use rand::{thread_rng, Rng};
-use std::collections::HashSet;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
 use yew::prelude::*;
 use yew::Callback;
 
-use crate::cell::{Cell, CellView, Wall};
+use crate::cell::{Cell, CellView, CellWalls, End, Terrain, Wall, RING};
+use crate::generator::{HuntAndKill, MazeGenerator, MazeRng};
+use crate::mask::Mask;
 use crate::position::{Direction, Position};
+use crate::topology::{ring_len, Topology};
+
+pub mod analysis;
+pub mod validate;
+pub mod walls;
+
+use walls::Walls;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "MazeFields", into = "MazeFields")
+)]
 pub struct Maze {
     pub m: usize,
     pub n: usize,
+    /// every floor's cells one after the other, bottom floor first
     pub cells: Vec<Cell>,
+    /// every wall between two cells, read and written through `wall` and
+    /// `set_wall`
+    pub walls: Walls,
+    pub topology: Topology,
+    /// number of floors stacked on top of each other
+    pub floors: usize,
+    /// whether each floor wraps around like a torus, the left edge joined
+    /// to the right and the top to the bottom. Only square and hex grids
+    /// wrap, hex ones only with an even number of rows
+    pub wrap: bool,
+    /// where the player's path starts
+    pub start: Position,
+    /// where the player's path has to reach
+    pub goal: Position,
+    /// seed the paths were generated from, so the Maze can be rebuilt
+    pub seed: u64,
+}
+
+/// how the start and goal of a Maze are picked
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Placement {
+    /// the first and last active cells, or from the outer ring to the
+    /// centre of a polar Maze. A wrapped Maze has its corners next to each
+    /// other, so its goal is the middle cell
+    Corners,
+    /// the two ends of the longest shortest path, found with a pass out
+    /// from any cell to the farthest one and a second pass back out from
+    /// there. A polar Maze keeps its goal at the centre and starts as far
+    /// from it as it gets
+    Farthest,
 }
 
 impl Maze {
     /// constructor for a completely closed-off Maze
     pub fn new(m: usize, n: usize) -> Self {
         Maze {
-            m: m,
-            n: n,
-            cells: (0..m * n)
-                .map(|_| Cell {
-                    left: Wall::Yes,
-                    right: Wall::Yes,
-                    up: Wall::Yes,
-                    down: Wall::Yes,
-                    clicked: false,
-                })
-                .collect(),
+            m,
+            n,
+            cells: vec![Cell::closed(); m * n],
+            walls: Walls::default(),
+            topology: Topology::Square,
+            floors: 1,
+            wrap: false,
+            start: Position { f: 0, r: 0, c: 0 },
+            goal: Position {
+                f: 0,
+                r: m.saturating_sub(1),
+                c: n.saturating_sub(1),
+            },
+            seed: 0,
+        }
+    }
+
+    /// constructor for a closed-off round Maze of `rings` rings around a
+    /// centre cell
+    pub fn polar(rings: usize) -> Self {
+        let topology = Topology::Polar;
+        Maze {
+            m: rings,
+            n: ring_len(rings - 1),
+            cells: vec![Cell::closed(); topology.row_start(rings, 0)],
+            walls: Walls::default(),
+            topology,
+            floors: 1,
+            wrap: false,
+            start: Position {
+                f: 0,
+                r: rings - 1,
+                c: ring_len(rings - 1) - 1,
+            },
+            goal: Position { f: 0, r: 0, c: 0 },
+            seed: 0,
+        }
+    }
+
+    /// constructor for a closed-off Maze cut to the shape of a Mask
+    pub fn with_mask(mask: &Mask) -> Self {
+        let mut maze = Self::new(mask.m, mask.n);
+        for (cell, &active) in maze.cells.iter_mut().zip(&mask.cells) {
+            cell.set_active(active);
+        }
+        maze.place_ends(Placement::Corners);
+        maze
+    }
+
+    /// stack `floors` copies of a single floor Maze on top of each other,
+    /// moving the goal up to the top floor
+    pub fn with_floors(self, floors: usize) -> Self {
+        let open: Vec<(Position, Direction)> = self
+            .positions()
+            .flat_map(|p| self.topology.directions().iter().map(move |&dir| (p, dir)))
+            .filter(|&(p, dir)| self.wall(p, dir) == Wall::No)
+            .collect();
+        let mut maze = Maze {
+            cells: self.cells.repeat(floors),
+            walls: Walls::default(),
+            floors,
+            goal: Position {
+                f: floors - 1,
+                ..self.goal
+            },
+            ..self
+        };
+        for f in 0..floors {
+            for &(p, dir) in &open {
+                maze.set_wall(Position { f, ..p }, dir, Wall::No);
+            }
+        }
+        maze
+    }
+
+    /// constructor for a Maze with only its outer boundary walled
+    pub fn new_open(m: usize, n: usize) -> Self {
+        let mut maze = Self::new(m, n);
+        maze.clear_walls();
+        maze
+    }
+
+    /// knock down every wall between cells, keeping the outer boundary
+    pub fn clear_walls(&mut self) {
+        for p in self.positions().collect::<Vec<_>>() {
+            for (dir, _) in self.neighbors(p) {
+                self.set_wall(p, dir, Wall::No);
+            }
         }
     }
 
     /// make a Maze like a new closed-off Maze
-    #[allow(dead_code)]
     pub fn reset_maze(&mut self) {
-        *self = Self::new(self.m, self.n);
+        for cell in self.cells.iter_mut() {
+            let active = cell.active();
+            *cell = Cell::closed();
+            cell.set_active(active);
+        }
+        self.walls = Walls::default();
+    }
+
+    /// mark `cells` as the hinted way on, taking down any hint shown before
+    pub fn show_hint(&mut self, cells: &[Position]) {
+        for cell in self.cells.iter_mut() {
+            cell.set_hinted(false);
+        }
+        for &p in cells {
+            self.cell_mut(p).set_hinted(true);
+        }
     }
 
     /// randomize the Maze with paths
-    pub fn cut_up_maze(&mut self, max_depth: usize) {
-        // since our closure mutates self we must get all
-        // our constants and information in variables prior
-        // to the mutable borrow in closure
-        let num_cells = self.m * self.n;
-        let (m, n) = (self.m, self.n);
-        let mut pos = Position { r: 0, c: 0 };
-        let mut visited: HashSet<Position> = HashSet::new();
-
-        let mut step = |p: Position,
-                        visited: &mut HashSet<Position>,
-                        depth_count: usize|
-         -> Option<Position> {
-            // add pos to visited
-            visited.insert(p);
-
-            // borrow and find valid neighbors
-            let mut neighbor_dirs = vec![];
-            for dir in vec![
-                Direction::Up,
-                Direction::Down,
-                Direction::Left,
-                Direction::Right,
-            ] {
-                if let Some(new_pos) = p.apply_move(dir) {
-                    // if starting a new section, make sure to connect something visited
-                    if depth_count == 0 {
-                        if self.is_position_valid(new_pos) && visited.contains(&new_pos) {
-                            neighbor_dirs.push(dir);
-                        }
-                    } else {
-                        if self.is_position_valid(new_pos) && !visited.contains(&new_pos) {
-                            neighbor_dirs.push(dir);
-                        }
-                    }
-                }
-            }
-            if neighbor_dirs.len() == 0 {
-                return None;
+    pub fn cut_up_maze(&mut self, max_depth: usize, rng: &mut MazeRng) {
+        HuntAndKill { max_depth }.generate(self, rng);
+    }
+
+    /// number of cells on each floor
+    pub fn floor_len(&self) -> usize {
+        self.topology.row_start(self.m, self.n)
+    }
+
+    /// where a position's cell is stored in `cells`
+    pub fn index(&self, p: Position) -> usize {
+        p.f * self.floor_len() + self.topology.row_start(p.r, self.n) + p.c
+    }
+
+    /// the position of the cell stored at `i` in `cells`, undoing `index`
+    pub fn position(&self, i: usize) -> Position {
+        let (f, i) = (i / self.floor_len(), i % self.floor_len());
+        let r = self.topology.row_of(i, self.n);
+        Position {
+            f,
+            r,
+            c: i - self.topology.row_start(r, self.n),
+        }
+    }
+
+    pub fn cell(&self, p: Position) -> &Cell {
+        &self.cells[self.index(p)]
+    }
+
+    pub fn cell_mut(&mut self, p: Position) -> &mut Cell {
+        let i = self.index(p);
+        &mut self.cells[i]
+    }
+
+    /// the cells of row `r` on floor `f`, which may be shorter than `n` on
+    /// a polar grid
+    pub fn row(&self, f: usize, r: usize) -> &[Cell] {
+        let start = self.index(Position { f, r, c: 0 });
+        &self.cells[start..start + self.topology.row_len(r, self.n)]
+    }
+
+    /// every active position, floor by floor and row by row
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..self.floors)
+            .flat_map(move |f| (0..self.m).map(move |r| (f, r)))
+            .flat_map(move |(f, r)| {
+                (0..self.topology.row_len(r, self.n)).map(move |c| Position { f, r, c })
+            })
+            .filter(move |p| self.cell(*p).active())
+    }
+
+    /// uniformly random active position
+    pub fn random_position(&self, rng: &mut MazeRng) -> Position {
+        *self.positions().collect::<Vec<_>>().choose(rng).unwrap()
+    }
+
+    /// pick the start on the bottom floor and the goal on the top floor
+    pub fn place_ends(&mut self, placement: Placement) {
+        let top = self.floors - 1;
+        let bottom: Vec<Position> = self.positions().filter(|p| p.f == 0).collect();
+        let centre = Position { f: top, r: 0, c: 0 };
+        (self.start, self.goal) = match (placement, self.topology) {
+            (Placement::Corners, Topology::Polar) => (*bottom.last().unwrap(), centre),
+            (Placement::Corners, _) if self.wrap => (
+                bottom[0],
+                Position {
+                    f: top,
+                    r: self.m / 2,
+                    c: self.n / 2,
+                },
+            ),
+            (Placement::Corners, _) => (bottom[0], self.positions().last().unwrap()),
+            (Placement::Farthest, Topology::Polar) => (self.farthest(centre, 0), centre),
+            (Placement::Farthest, _) => {
+                let start = self.farthest(bottom[0], 0);
+                (start, self.farthest(start, top))
             }
+        };
+    }
 
-            // get mutable reference to cell
-            // and get random direction to neighbor
-            let dir = neighbor_dirs[thread_rng().gen_range(0..neighbor_dirs.len())];
-            let new_pos = p.apply_move(dir).unwrap();
-            match dir {
-                Direction::Left => {
-                    self.cells[p.r * self.n + p.c].left = Wall::No;
-                    self.cells[new_pos.r * self.n + new_pos.c].right = Wall::No;
-                }
-                Direction::Right => {
-                    self.cells[p.r * self.n + p.c].right = Wall::No;
-                    self.cells[new_pos.r * self.n + new_pos.c].left = Wall::No;
-                }
-                Direction::Down => {
-                    self.cells[p.r * self.n + p.c].down = Wall::No;
-                    self.cells[new_pos.r * self.n + new_pos.c].up = Wall::No;
-                }
-                Direction::Up => {
-                    self.cells[p.r * self.n + p.c].up = Wall::No;
-                    self.cells[new_pos.r * self.n + new_pos.c].down = Wall::No;
+    /// the cell on floor `f` farthest along the paths from `from`
+    fn farthest(&self, from: Position, f: usize) -> Position {
+        let distances = self.distances(from);
+        self.positions()
+            .filter(|p| p.f == f)
+            .max_by_key(|p| distances[self.index(*p)])
+            .unwrap()
+    }
+
+    /// number of steps along the paths from `from` to every cell, indexed
+    /// like `cells`. None for cells that can't be reached
+    pub fn distances(&self, from: Position) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.cells.len()];
+        distances[self.index(from)] = Some(0);
+        let mut queue = VecDeque::from([from]);
+        while let Some(p) = queue.pop_front() {
+            let d = distances[self.index(p)].unwrap();
+            for (_, new_pos) in self.links(p) {
+                let i = self.index(new_pos);
+                if distances[i].is_none() {
+                    distances[i] = Some(d + 1);
+                    queue.push_back(new_pos);
                 }
             }
-            Some(new_pos)
+        }
+        distances
+    }
+
+    /// how far along the paths every cell is from one end of the Maze,
+    /// scaled so that end is 0.0 and the farthest cell from it 1.0. Indexed
+    /// like `cells`, None for cells that can't be reached
+    pub fn heat(&self, from: End) -> Vec<Option<f64>> {
+        let distances = self.distances(match from {
+            End::Start => self.start,
+            End::Goal => self.goal,
+        });
+        let farthest = distances.iter().flatten().max().copied().unwrap_or(0);
+        distances
+            .into_iter()
+            .map(|d| d.map(|d| d as f64 / usize::max(farthest, 1) as f64))
+            .collect()
+    }
+
+    /// whether the Maze is a single floor of square cells with edges that
+    /// don't wrap, the only kind row-based generators can carve
+    pub fn is_plain(&self) -> bool {
+        self.topology == Topology::Square && self.floors == 1 && !self.wrap
+    }
+
+    /// the position one step from `p` in `dir`, tunnelling on under a
+    /// crossing that passage runs beneath
+    fn step(&self, p: Position, dir: Direction) -> Option<Position> {
+        let q = self.grid_step(p, dir)?;
+        if self.active_cell(q).is_some_and(|cell| cell.tunnels(dir)) {
+            return self.grid_step(q, dir);
+        }
+        Some(q)
+    }
+
+    /// the position one step from `p` in `dir`, going around the edges of a
+    /// wrapped Maze. A wrapped Maze one cell wide or high has no neighbor
+    /// across that way, rather than the cell itself
+    fn grid_step(&self, p: Position, dir: Direction) -> Option<Position> {
+        if !self.wrap {
+            return self.topology.step(p, dir);
+        }
+        // step from a copy of the Maze two down and one across, so nothing
+        // falls off the top or left and hex rows keep their shift, then
+        // fold back onto the Maze
+        let far = Position {
+            r: p.r + 2 * self.m,
+            c: p.c + self.n,
+            ..p
         };
+        self.topology
+            .step(far, dir)
+            .map(|q| Position {
+                r: q.r % self.m,
+                c: q.c % self.n,
+                ..q
+            })
+            .filter(|&q| q != p)
+    }
 
-        let mut depth_count = 0;
-        while visited.len() < num_cells {
-            match (
-                depth_count < max_depth,
-                step(pos, &mut visited, depth_count),
-            ) {
-                (true, Some(p)) => {
-                    pos = p;
-                    depth_count += 1;
-                }
-                _ => {
-                    depth_count = 0;
-                    'outer: for i in 0..m {
-                        for j in 0..n {
-                            let p = Position { r: i, c: j };
-                            if !visited.contains(&p) {
-                                pos = p;
-                                break 'outer;
-                            }
-                        }
-                    }
-                }
+    /// whether the way out of a position in `dir` leads around to the far
+    /// side of a wrapped Maze
+    pub fn wraps(&self, p: Position, dir: Direction) -> bool {
+        self.wrap
+            && self.neighbor(p, dir).is_some()
+            && self
+                .topology
+                .step(p, dir)
+                .is_none_or(|q| q.r >= self.m || q.c >= self.n)
+    }
+
+    /// the active neighbor of a position in `dir`, if it has one. A
+    /// crossing only leads on along its own corridor, the passage beneath
+    /// it links the cells on either side
+    pub fn neighbor(&self, p: Position, dir: Direction) -> Option<Position> {
+        if self.active_cell(p).is_none_or(|cell| cell.tunnels(dir)) {
+            return None;
+        }
+        self.step(p, dir)
+            .filter(|new_pos| self.is_position_valid(*new_pos))
+    }
+
+    /// all active neighbors of a position along with the direction to reach
+    /// them, stairs included when there is more than one floor
+    pub fn neighbors(&self, p: Position) -> Vec<(Direction, Position)> {
+        self.directions()
+            .filter_map(|dir| self.neighbor(p, dir).map(|new_pos| (dir, new_pos)))
+            .collect()
+    }
+
+    /// directions cells can lead in, the stairs included when there is
+    /// more than one floor
+    pub fn directions(&self) -> impl Iterator<Item = Direction> {
+        let stairs: &[Direction] = if self.floors > 1 {
+            &[Direction::Above, Direction::Below]
+        } else {
+            &[]
+        };
+        self.topology.directions().iter().chain(stairs).copied()
+    }
+
+    /// knock down the wall between a position and its neighbor in `dir`
+    /// on both sides, returning the neighbor
+    pub fn carve(&mut self, p: Position, dir: Direction) -> Position {
+        let new_pos = self.step(p, dir).unwrap();
+        let back = self.topology.back(p, dir);
+        self.set_wall(p, dir, Wall::No);
+        self.set_wall(new_pos, back, Wall::No);
+        new_pos
+    }
+
+    /// put up the wall between a position and its neighbor in `dir`
+    /// on both sides, returning the neighbor
+    pub fn build_wall(&mut self, p: Position, dir: Direction) -> Position {
+        let new_pos = self.step(p, dir).unwrap();
+        let back = self.topology.back(p, dir);
+        self.set_wall(p, dir, Wall::Yes);
+        self.set_wall(new_pos, back, Wall::Yes);
+        new_pos
+    }
+
+    /// neighbors that can be walked to from a position without crossing a wall
+    pub fn links(&self, p: Position) -> Vec<(Direction, Position)> {
+        self.neighbors(p)
+            .into_iter()
+            .filter(|(dir, _)| self.wall(p, *dir) == Wall::No)
+            .collect()
+    }
+
+    /// turn a perfect Maze into a braid Maze by knocking an extra wall out of
+    /// `factor` (0.0-1.0) of its dead ends, which opens up loops. Dead ends
+    /// next to each other are joined first so one wall clears two of them
+    pub fn braid(&mut self, factor: f64, rng: &mut MazeRng) {
+        let mut dead_ends: Vec<Position> = self
+            .positions()
+            .filter(|p| self.links(*p).len() == 1)
+            .collect();
+        dead_ends.shuffle(rng);
+        let count = (factor.clamp(0.0, 1.0) * dead_ends.len() as f64).round() as usize;
+
+        for &p in &dead_ends[..count] {
+            // an earlier pick may have opened this one up already
+            if self.links(p).len() != 1 {
+                continue;
             }
+            let closed: Vec<(Direction, Position)> = self
+                .neighbors(p)
+                .into_iter()
+                .filter(|(dir, _)| self.wall(p, *dir) == Wall::Yes)
+                .collect();
+            let paired: Vec<Direction> = closed
+                .iter()
+                .filter(|(_, new_pos)| self.links(*new_pos).len() == 1)
+                .map(|(dir, _)| *dir)
+                .collect();
+            // the RNG is only drawn from for the list picked from. A dead
+            // end in a narrow spot of a masked shape may have no wall left
+            // to open
+            let choice = match paired.choose(rng) {
+                Some(dir) => Some(*dir),
+                None => closed.choose(rng).map(|(dir, _)| *dir),
+            };
+            let Some(dir) = choice else {
+                continue;
+            };
+            self.carve(p, dir);
         }
     }
 
+    /// cover each cell but the start and goal in rough terrain with chance
+    /// `fraction` (0.0-1.0), the kind of terrain picked at random
+    pub fn scatter_terrain(&mut self, fraction: f64, rng: &mut MazeRng) {
+        for p in self.positions().collect::<Vec<_>>() {
+            if p != self.start && p != self.goal && rng.gen_bool(fraction.clamp(0.0, 1.0)) {
+                self.cell_mut(p)
+                    .set_terrain(*Terrain::ROUGH.choose(rng).unwrap());
+            }
+        }
+    }
+
+    /// whether any cell costs more than bare floor to cross
+    pub fn is_weighted(&self) -> bool {
+        self.cells
+            .iter()
+            .any(|cell| cell.terrain() != Terrain::Floor)
+    }
+
+    /// whether any cell is a crossing with a passage tunnelling under it
+    pub fn is_woven(&self) -> bool {
+        self.cells.iter().any(|cell| cell.under().is_some())
+    }
+
     pub fn is_connected(&self, start: Position, stop: Position, path: &HashSet<Position>) -> bool {
+        self.reachable(start, path).contains(&stop)
+    }
+
+    /// every position on `path` that can be walked to from `start`
+    pub fn reachable(&self, start: Position, path: &HashSet<Position>) -> HashSet<Position> {
         // let's do BFS starting from our start position
         let mut queue: Vec<Position> = vec![start];
-        let mut visited: HashSet<Position> = HashSet::new();
+        let mut visited: HashSet<Position> = HashSet::from([start]);
 
-        while queue.len() > 0 {
+        while !queue.is_empty() {
             let mut tmp = vec![];
             for p in &queue {
-                for dir in vec![
-                    Direction::Up,
-                    Direction::Down,
-                    Direction::Left,
-                    Direction::Right,
-                ] {
-                    if let Some(new_pos) = p.apply_move(dir) {
-                        // check if new_pos in path
-                        if path.contains(&new_pos) && !visited.contains(&new_pos) {
-                            // check correct walls are missing
-                            match dir {
-                                Direction::Left => {
-                                    // check left wall of pos and check right wall of new_pos
-                                    if self.cells[p.r * self.n + p.c].left == Wall::No
-                                        && self.cells[new_pos.r * self.n + new_pos.c].right
-                                            == Wall::No
-                                    {
-                                        if new_pos == stop {
-                                            return true;
-                                        }
-                                        tmp.push(new_pos);
-                                    }
-                                }
-                                Direction::Right => {
-                                    if self.cells[p.r * self.n + p.c].right == Wall::No
-                                        && self.cells[new_pos.r * self.n + new_pos.c].left
-                                            == Wall::No
-                                    {
-                                        if new_pos == stop {
-                                            return true;
-                                        }
-                                        tmp.push(new_pos);
-                                    }
-                                }
-                                Direction::Up => {
-                                    if self.cells[p.r * self.n + p.c].up == Wall::No
-                                        && self.cells[new_pos.r * self.n + new_pos.c].down
-                                            == Wall::No
-                                    {
-                                        if new_pos == stop {
-                                            return true;
-                                        }
-                                        tmp.push(new_pos);
-                                    }
-                                }
-                                Direction::Down => {
-                                    if self.cells[p.r * self.n + p.c].down == Wall::No
-                                        && self.cells[new_pos.r * self.n + new_pos.c].up == Wall::No
-                                    {
-                                        if new_pos == stop {
-                                            return true;
-                                        }
-                                        tmp.push(new_pos);
-                                    }
-                                }
-                            }
+                for (dir, new_pos) in self.neighbors(*p) {
+                    // check if new_pos in path
+                    if path.contains(&new_pos) && !visited.contains(&new_pos) {
+                        // check the walls on both sides are missing
+                        if self.wall(*p, dir) == Wall::No
+                            && self.wall(new_pos, self.topology.back(*p, dir)) == Wall::No
+                        {
+                            visited.insert(new_pos);
+                            tmp.push(new_pos);
                         }
-                        visited.insert(p.clone());
                     }
                 }
             }
             queue = tmp;
         }
-        false
+        visited
     }
 
     pub fn is_position_valid(&self, p: Position) -> bool {
-        p.r < self.m && p.c < self.n
+        self.active_cell(p).is_some()
+    }
+
+    /// the cell at a position, if it's on the grid and active
+    fn active_cell(&self, p: Position) -> Option<&Cell> {
+        self.in_grid(p)
+            .then(|| self.cell(p))
+            .filter(|cell| cell.active())
+    }
+
+    /// whether a position lies on the grid, whether it's active or not
+    fn in_grid(&self, p: Position) -> bool {
+        p.f < self.floors && p.r < self.m && p.c < self.topology.row_len(p.r, self.n)
+    }
+
+    /// whether the wall of `p` in `dir` is up. A wall with no cell behind
+    /// it always is
+    pub fn wall(&self, p: Position, dir: Direction) -> Wall {
+        match self.wall_slot(p, dir) {
+            Some((i, side)) if self.walls.is_open(side, i) => Wall::No,
+            _ => Wall::Yes,
+        }
+    }
+
+    /// put up or knock down the wall of `p` in `dir`, which is the same
+    /// wall as the one on the other side of it. A wall with no cell behind
+    /// it stays up
+    pub fn set_wall(&mut self, p: Position, dir: Direction, wall: Wall) {
+        if let Some((i, side)) = self.wall_slot(p, dir) {
+            let len = self.cells.len();
+            self.walls.set_open(side, i, wall == Wall::No, len);
+        }
+    }
+
+    /// every wall around `p`, for drawing it
+    pub fn cell_walls(&self, p: Position) -> CellWalls {
+        CellWalls::from_fn(|dir| self.wall(p, dir))
+    }
+
+    /// the index of the cell keeping the wall of `p` in `dir` and the side
+    /// of it the wall is on, if there's a cell on the other side at all.
+    /// A passage under a crossing goes through two walls, one each side
+    fn wall_slot(&self, p: Position, dir: Direction) -> Option<(usize, Direction)> {
+        if !self.in_grid(p) {
+            return None;
+        }
+        let q = self.grid_step(p, dir).filter(|q| self.in_grid(*q))?;
+        if self.topology.owned().contains(&dir) {
+            Some((self.index(p), dir))
+        } else {
+            Some((self.index(q), self.topology.back(p, dir)))
+        }
+    }
+}
+
+/// a Maze the way saves write it, with its walls listed cell by cell as
+/// the sides each cell has open of the ones it keeps
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MazeFields {
+    m: usize,
+    n: usize,
+    cells: Vec<Cell>,
+    walls: Vec<Vec<Direction>>,
+    topology: Topology,
+    floors: usize,
+    wrap: bool,
+    start: Position,
+    goal: Position,
+    #[serde(with = "crate::save::seed")]
+    seed: u64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<MazeFields> for Maze {
+    type Error = String;
+
+    /// fails on walls that don't line up with the cells, or that are open
+    /// on a side its cell doesn't keep
+    fn try_from(fields: MazeFields) -> Result<Self, String> {
+        let len = fields.cells.len();
+        if fields.walls.len() != len {
+            return Err(format!(
+                "walls for {} cells, not {}",
+                fields.walls.len(),
+                len
+            ));
+        }
+        let mut walls = Walls::default();
+        for (i, open) in fields.walls.into_iter().enumerate() {
+            for dir in open {
+                if !fields.topology.owned().contains(&dir) {
+                    return Err(format!("cell {} keeps no wall on its {:?} side", i, dir));
+                }
+                walls.set_open(dir, i, true, len);
+            }
+        }
+        Ok(Maze {
+            m: fields.m,
+            n: fields.n,
+            cells: fields.cells,
+            walls,
+            topology: fields.topology,
+            floors: fields.floors,
+            wrap: fields.wrap,
+            start: fields.start,
+            goal: fields.goal,
+            seed: fields.seed,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Maze> for MazeFields {
+    fn from(maze: Maze) -> Self {
+        let walls = (0..maze.cells.len())
+            .map(|i| {
+                maze.topology
+                    .owned()
+                    .iter()
+                    .filter(|&&dir| maze.walls.is_open(dir, i))
+                    .copied()
+                    .collect()
+            })
+            .collect();
+        MazeFields {
+            m: maze.m,
+            n: maze.n,
+            cells: maze.cells,
+            walls,
+            topology: maze.topology,
+            floors: maze.floors,
+            wrap: maze.wrap,
+            start: maze.start,
+            goal: maze.goal,
+            seed: maze.seed,
+        }
+    }
+}
+
+/// which end of the Maze's path a position is, if either
+fn end_at(maze: &Maze, p: Position) -> Option<End> {
+    if p == maze.start {
+        Some(End::Start)
+    } else if p == maze.goal {
+        Some(End::Goal)
+    } else {
+        None
     }
 }
 
 pub enum Msg {
     Reset,
+    Floor(usize),
+    Heatmap,
+}
+
+pub struct MazeView {
+    /// which end the cells are tinted by their distance from, if the
+    /// heatmap is showing
+    heatmap: Option<End>,
+    /// `Maze::heat` from that end, worked out once when it's turned on.
+    /// Empty while the heatmap is off
+    heat: Vec<Option<f64>>,
+    /// walls and ends of the Maze on show, so the heatmap is turned off
+    /// when a different Maze comes in
+    layout: (Walls, Position, Position),
 }
 
-pub struct MazeView;
+/// what tells one Maze from another, its cells' markings aside
+fn layout(maze: &Maze) -> (Walls, Position, Position) {
+    (maze.walls.clone(), maze.start, maze.goal)
+}
 
 #[derive(Clone, Debug, PartialEq, Properties)]
 pub struct MazeViewProps {
     pub maze: Maze,
-    pub click_callback: Callback<(usize, usize)>,
+    /// which floor of the Maze is on show
+    pub floor: usize,
+    /// whether the heatmap can be turned on
+    #[prop_or_default]
+    pub heatmap: bool,
+    pub click_callback: Callback<Position>,
     pub reset_callback: Callback<()>,
+    pub floor_callback: Callback<usize>,
 }
 
 impl Component for MazeView {
     type Message = Msg;
     type Properties = MazeViewProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Self
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            heatmap: None,
+            heat: vec![],
+            layout: layout(&ctx.props().maze),
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        let maze = &ctx.props().maze;
+        let new_maze = maze.walls != self.layout.0
+            || maze.start != self.layout.1
+            || maze.goal != self.layout.2;
+        if new_maze {
+            self.layout = layout(maze);
+        }
+        if new_maze || !ctx.props().heatmap {
+            self.heatmap = None;
+            self.heat.clear();
+        }
+        true
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
+            // off, then from the start, then from the goal
+            Msg::Heatmap => {
+                self.heatmap = match self.heatmap {
+                    None => Some(End::Start),
+                    Some(End::Start) => Some(End::Goal),
+                    Some(End::Goal) => None,
+                };
+                self.heat = match self.heatmap {
+                    Some(end) => ctx.props().maze.heat(end),
+                    None => vec![],
+                };
+                true
+            }
             Msg::Reset => {
                 ctx.props().reset_callback.emit(());
                 true
             }
+            Msg::Floor(floor) => {
+                ctx.props().floor_callback.emit(floor);
+                true
+            }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let link = ctx.link();
+        let maze_rows = match ctx.props().maze.topology {
+            Topology::Polar => self.polar_view(ctx),
+            _ => self.rows_view(ctx),
+        };
+        html! {
+            <div>
+                if ctx.props().maze.floors > 1 {
+                    <p>
+                        {
+                            (0..ctx.props().maze.floors).map(|floor| html! {
+                                <button onclick={link.callback(move |_| Msg::Floor(floor))}
+                                disabled={ floor == ctx.props().floor }>
+                                    { format!("Floor {}", floor + 1) }
+                                </button>
+                            }).collect::<Html>()
+                        }
+                    </p>
+                }
+                { maze_rows }
+                <button onclick={link.callback(|_| Msg::Reset)}>{ "Reset Maze" }</button>
+                if ctx.props().heatmap {
+                    <button onclick={link.callback(|_| Msg::Heatmap)}>
+                        {
+                            match self.heatmap {
+                                None => "Heatmap: off",
+                                Some(End::Start) => "Heatmap: from start",
+                                Some(End::Goal) => "Heatmap: from goal",
+                            }
+                        }
+                    </button>
+                }
+            </div>
+        }
+    }
+}
+
+impl MazeView {
+    /// one svg centred on the middle of the Maze, each ring cell drawing
+    /// itself around that centre
+    fn polar_view(&self, ctx: &Context<Self>) -> Html {
+        let maze = &ctx.props().maze;
+        let floor = ctx.props().floor;
+        let size = 2 * (maze.m * RING + 5);
+        let view_box = format!("-{0} -{0} {1} {1}", size / 2, size);
+        html! {
+            <svg width={ size.to_string() } height={ size.to_string() } viewBox={ view_box }>
+                {
+                    maze.positions().filter(|p| p.f == floor).map(|p| html!{
+                        < CellView
+                            cell={ *maze.cell(p) }
+                            walls={ maze.cell_walls(p) }
+                            pos={ p }
+                            topology={ maze.topology }
+                            end={ end_at(maze, p) }
+                            heat={ self.heat.get(maze.index(p)).copied().flatten() }
+                            cell_clicked={ ctx.props().click_callback.clone() }
+                        />
+                    }).collect::<Html>()
+                }
+            </svg>
+        }
+    }
+
+    fn rows_view(&self, ctx: &Context<Self>) -> Html {
+        let maze = &ctx.props().maze;
+        let floor = ctx.props().floor;
         let mut maze_rows = vec![];
         for row_num in 0..ctx.props().maze.m {
+            let style = match ctx.props().maze.topology {
+                Topology::Square => "margin:0; padding:0;".to_string(),
+                // hex rows tuck a quarter of a cell into the row above,
+                // odd rows shifted half a cell to the right
+                Topology::Hex => format!(
+                    "display:flex; margin:{}px 0 0 0; padding:0 0 0 {}px;",
+                    if row_num == 0 { 0 } else { -23 },
+                    if row_num % 2 == 1 { 40 } else { 0 },
+                ),
+                // rings are drawn by polar_view
+                Topology::Polar => unreachable!(),
+            };
             maze_rows.push(html! {
-                <p style="margin:0; padding:0;" >
+                <p style={ style } >
                     {
-                        ctx.props().maze.cells[row_num*ctx.props().maze.n..(row_num+1)*ctx.props().maze.n]
+                        ctx.props().maze.row(floor, row_num)
                             .iter()
                             .enumerate()
-                            .map(|(j, cell)| html!{
-                                < CellView
-                                    cell={ cell.clone() }
-                                    pos={ (row_num, j) }
-                                    cell_clicked={ ctx.props().click_callback.clone() }
-                                />
+                            .map(|(j, cell)| {
+                                let pos = Position { f: floor, r: row_num, c: j };
+                                let wrapped: Vec<Direction> = maze.topology.directions()
+                                    .iter()
+                                    .filter(|&&dir| maze.wall(pos, dir) == Wall::No && maze.wraps(pos, dir))
+                                    .copied()
+                                    .collect();
+                                html!{
+                                    < CellView
+                                        cell={ *cell }
+                                        walls={ maze.cell_walls(pos) }
+                                        pos={ pos }
+                                        topology={ maze.topology }
+                                        wrapped={ wrapped }
+                                        end={ end_at(maze, pos) }
+                                        heat={ self.heat.get(maze.index(pos)).copied().flatten() }
+                                        cell_clicked={ ctx.props().click_callback.clone() }
+                                    />
+                                }
                         }).collect::<Html>()
                     }
                 </p>
             });
         }
-        html! {
-            <div>
-                { maze_rows }
-                <button onclick={link.callback(|_| Msg::Reset)}>{ "Reset Maze" }</button>
-            </div>
+        maze_rows.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::generator::Kruskal;
+    use crate::maze::validate::Layout;
+
+    fn dead_ends(maze: &Maze) -> usize {
+        maze.positions()
+            .filter(|p| maze.links(*p).len() == 1)
+            .count()
+    }
+
+    #[test]
+    fn full_braid_opens_every_dead_end() {
+        for seed in 0..5 {
+            let mut rng = MazeRng::seed_from_u64(seed);
+            let mut maze = Maze::new(10, 10);
+            Kruskal.generate(&mut maze, &mut rng);
+            assert!(dead_ends(&maze) > 0);
+            maze.braid(1.0, &mut rng);
+            assert_eq!(dead_ends(&maze), 0, "seed {}", seed);
+            assert!(matches!(maze.validate(), Ok(Layout::Loops(_))));
+        }
+    }
+
+    #[test]
+    fn thin_wrapped_mazes_have_no_self_neighbors() {
+        for (m, n) in [(1, 1), (1, 6), (6, 1)] {
+            for name in crate::generator::NAMES {
+                let mut maze = Maze {
+                    wrap: true,
+                    ..Maze::new(m, n)
+                };
+                for p in maze.positions().collect::<Vec<_>>() {
+                    assert!(maze.neighbors(p).iter().all(|&(_, q)| q != p));
+                }
+                let generator = crate::generator::from_name(name).unwrap();
+                generator.generate(&mut maze, &mut MazeRng::seed_from_u64(1));
+                maze.place_ends(Placement::Corners);
+                assert!(maze.validate().is_ok(), "{} on {}x{}", name, m, n);
+            }
+        }
+    }
+
+    #[test]
+    fn new_hints_replace_old_ones() {
+        let mut maze = Maze::new(3, 3);
+        let at = |r, c| Position { f: 0, r, c };
+        maze.show_hint(&[at(0, 0), at(0, 1)]);
+        maze.show_hint(&[at(1, 1)]);
+        let hinted: Vec<Position> = maze
+            .positions()
+            .filter(|p| maze.cell(*p).hinted())
+            .collect();
+        assert_eq!(hinted, [at(1, 1)]);
+    }
+
+    #[test]
+    fn heat_runs_from_the_end_to_the_farthest_cell() {
+        let rng = &mut MazeRng::seed_from_u64(3);
+        let mut maze = Maze::with_mask(&Mask::heart(15, 15));
+        HuntAndKill::default().generate(&mut maze, rng);
+        for (end, at) in [(End::Start, maze.start), (End::Goal, maze.goal)] {
+            let heat = maze.heat(end);
+            assert_eq!(heat[maze.index(at)], Some(0.0));
+            let hottest = heat.iter().flatten().copied().fold(0.0, f64::max);
+            assert_eq!(hottest, 1.0);
+            for (cell, heat) in maze.cells.iter().zip(&heat) {
+                assert_eq!(cell.active(), heat.is_some());
+            }
         }
     }
 }
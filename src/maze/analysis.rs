@@ -0,0 +1,148 @@
+use super::Maze;
+
+/// numbers describing how hard a Maze is to solve
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    /// cells that can be walked into
+    pub cells: usize,
+    /// cells on the shortest path from start to goal, counting both ends.
+    /// None if the goal can't be reached
+    pub solution_length: Option<usize>,
+    /// cells with a single way in and out
+    pub dead_ends: usize,
+    /// cells with three or more ways out
+    pub junctions: usize,
+    /// cells in the longest run of passage cells, which have exactly two
+    /// ways out
+    pub longest_corridor: usize,
+    /// ways on from a cell not counting the way in, averaged over every
+    /// cell that can be walked into
+    pub branching_factor: f64,
+    /// share of cells that are passages, with exactly two ways out. Mazes
+    /// mostly made of passages run in long corridors, ones with few are
+    /// full of short dead ends and forks
+    pub passage_share: f64,
+}
+
+impl Analysis {
+    pub fn of(maze: &Maze) -> Self {
+        let mut links = vec![0; maze.cells.len()];
+        for p in maze.positions() {
+            links[maze.index(p)] = maze.links(p).len();
+        }
+        let open: Vec<usize> = maze
+            .positions()
+            .map(|p| links[maze.index(p)])
+            .filter(|&count| count > 0)
+            .collect();
+        let passages = open.iter().filter(|&&count| count == 2).count();
+
+        Analysis {
+            cells: open.len(),
+            solution_length: maze.distances(maze.start)[maze.index(maze.goal)].map(|d| d + 1),
+            dead_ends: open.iter().filter(|&&count| count == 1).count(),
+            junctions: open.iter().filter(|&&count| count >= 3).count(),
+            longest_corridor: longest_corridor(maze, &links),
+            branching_factor: if open.is_empty() {
+                0.0
+            } else {
+                open.iter().map(|&count| count - 1).sum::<usize>() as f64 / open.len() as f64
+            },
+            passage_share: if open.is_empty() {
+                0.0
+            } else {
+                passages as f64 / open.len() as f64
+            },
+        }
+    }
+
+    /// difficulty from 1 to 5 to show players. Longer solutions rate
+    /// harder, and more so the more dead ends there are to wander into
+    pub fn stars(&self) -> usize {
+        let length = self.solution_length.unwrap_or(0) as f64;
+        let score = length * (1.0 + self.dead_ends as f64 / self.cells.max(1) as f64);
+        (score / 4.0).log2().round().clamp(1.0, 5.0) as usize
+    }
+}
+
+/// size of the largest group of passage cells linked to each other, each
+/// group being a single corridor or a loop
+fn longest_corridor(maze: &Maze, links: &[usize]) -> usize {
+    let mut seen = vec![false; maze.cells.len()];
+    let mut longest = 0;
+    for p in maze.positions() {
+        if links[maze.index(p)] != 2 || seen[maze.index(p)] {
+            continue;
+        }
+        seen[maze.index(p)] = true;
+        let mut stack = vec![p];
+        let mut length = 0;
+        while let Some(p) = stack.pop() {
+            length += 1;
+            for (_, new_pos) in maze.links(p) {
+                let i = maze.index(new_pos);
+                if links[i] == 2 && !seen[i] {
+                    seen[i] = true;
+                    stack.push(new_pos);
+                }
+            }
+        }
+        longest = longest.max(length);
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::{Direction, Position};
+
+    /// a Maze of a single row with its walls knocked down from one end to
+    /// the other
+    fn corridor(n: usize) -> Maze {
+        let mut maze = Maze::new(1, n);
+        for c in 0..n - 1 {
+            maze.carve(Position { f: 0, r: 0, c }, Direction::Right);
+        }
+        maze
+    }
+
+    #[test]
+    fn corridor_is_all_passage() {
+        let analysis = Analysis::of(&corridor(6));
+        assert_eq!(analysis.cells, 6);
+        assert_eq!(analysis.solution_length, Some(6));
+        assert_eq!((analysis.dead_ends, analysis.junctions), (2, 0));
+        assert_eq!(analysis.longest_corridor, 4);
+        assert_eq!(analysis.passage_share, 4.0 / 6.0);
+        assert_eq!(analysis.branching_factor, 4.0 / 6.0);
+    }
+
+    #[test]
+    fn junctions_and_unreachable_goals() {
+        // a T: the top row runs across and the middle leads down from it
+        let mut maze = Maze::new(2, 3);
+        let at = |r, c| Position { f: 0, r, c };
+        maze.carve(at(0, 0), Direction::Right);
+        maze.carve(at(0, 1), Direction::Right);
+        maze.carve(at(0, 1), Direction::Down);
+        maze.goal = at(1, 1);
+        let analysis = Analysis::of(&maze);
+        assert_eq!(analysis.cells, 4);
+        assert_eq!(analysis.solution_length, Some(3));
+        assert_eq!((analysis.dead_ends, analysis.junctions), (3, 1));
+        assert_eq!(analysis.longest_corridor, 0);
+
+        maze.goal = at(1, 2);
+        assert_eq!(Analysis::of(&maze).solution_length, None);
+    }
+
+    #[test]
+    fn stars_grow_with_the_solution() {
+        let stars: Vec<usize> = [2, 8, 16, 32, 64, 200]
+            .into_iter()
+            .map(|n| Analysis::of(&corridor(n)).stars())
+            .collect();
+        assert_eq!(stars, [1, 1, 2, 3, 4, 5]);
+    }
+}
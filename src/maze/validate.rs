@@ -0,0 +1,236 @@
+use std::fmt;
+
+use super::Maze;
+use crate::cell::{End, Wall};
+use crate::position::{Direction, Position};
+
+/// something wrong with the walls of a Maze
+#[derive(Clone, Debug, PartialEq)]
+pub enum MazeError {
+    /// the wall of `pos` in `dir` and the same wall seen from the neighbor
+    /// on the other side don't agree on whether it's there
+    MismatchedWall { pos: Position, dir: Direction },
+    /// the wall of `pos` in `dir` is open, but there's no cell behind it
+    OpenBoundary { pos: Position, dir: Direction },
+    /// `pos` can't be reached from the start
+    Unreachable { pos: Position },
+    /// the start or goal at `pos` isn't an active cell of the grid
+    BadEnd { end: End, pos: Position },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MazeError::MismatchedWall { pos, dir } => {
+                write!(
+                    f,
+                    "the {:?} wall of {:?} differs from its other side",
+                    dir, pos
+                )
+            }
+            MazeError::OpenBoundary { pos, dir } => {
+                write!(f, "the {:?} wall of {:?} opens onto nothing", dir, pos)
+            }
+            MazeError::Unreachable { pos } => write!(f, "{:?} can't be reached", pos),
+            MazeError::BadEnd { end, pos } => {
+                write!(f, "the {:?} at {:?} isn't a cell of the maze", end, pos)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
+
+/// how the paths of a valid Maze fit together
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layout {
+    /// exactly one way between any two cells
+    Perfect,
+    /// this many passages more than a perfect Maze, each one closing a loop
+    Loops(usize),
+}
+
+impl Maze {
+    /// check that the start and goal are cells of the Maze, both sides of
+    /// every wall agree, the edges of the grid and of its shape are walled
+    /// off, and every cell can be reached from the start. Returns every
+    /// problem found, or how the paths fit together if there were none
+    pub fn validate(&self) -> Result<Layout, Vec<MazeError>> {
+        let mut errors: Vec<MazeError> = [(End::Start, self.start), (End::Goal, self.goal)]
+            .into_iter()
+            .filter(|&(_, pos)| !self.is_position_valid(pos))
+            .map(|(end, pos)| MazeError::BadEnd { end, pos })
+            .collect();
+        let mut passages = 0;
+        let dirs = self.topology.directions().iter();
+        for p in self.positions() {
+            for &dir in dirs.clone().chain(&[Direction::Above, Direction::Below]) {
+                // a crossing's open sides belong to the passage beneath it
+                if self.cell(p).tunnels(dir) {
+                    continue;
+                }
+                let wall = self.wall(p, dir);
+                match self.neighbor(p, dir) {
+                    Some(q) => {
+                        let back = self.topology.back(p, dir);
+                        if self.index(q) > self.index(p) && self.wall(q, back) != wall {
+                            errors.push(MazeError::MismatchedWall { pos: p, dir });
+                        }
+                        if wall == Wall::No {
+                            passages += 1;
+                        }
+                    }
+                    None if wall == Wall::No => {
+                        errors.push(MazeError::OpenBoundary { pos: p, dir });
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        // there's nothing to measure from without a start
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let distances = self.distances(self.start);
+        errors.extend(
+            self.positions()
+                .filter(|p| distances[self.index(*p)].is_none())
+                .map(|pos| MazeError::Unreachable { pos }),
+        );
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        // every passage was counted from both ends, and a tree over the
+        // cells has one passage fewer than it has cells. The start is a
+        // cell, so there's at least one
+        let cells = self.positions().count();
+        match passages / 2 + 1 - cells {
+            0 => Ok(Layout::Perfect),
+            loops => Ok(Layout::Loops(loops)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::generator::{Kruskal, MazeGenerator, MazeRng};
+
+    fn at(r: usize, c: usize) -> Position {
+        Position { f: 0, r, c }
+    }
+
+    /// a perfect 4x4 Maze to break
+    fn perfect() -> Maze {
+        let mut maze = Maze::new(4, 4);
+        Kruskal.generate(&mut maze, &mut MazeRng::seed_from_u64(1));
+        assert_eq!(maze.validate(), Ok(Layout::Perfect));
+        maze
+    }
+
+    #[test]
+    fn loops_are_counted() {
+        let mut maze = perfect();
+        let closed: Vec<(Position, Direction)> = maze
+            .positions()
+            .flat_map(|p| maze.neighbors(p).into_iter().map(move |(dir, _)| (p, dir)))
+            .filter(|&(p, dir)| maze.wall(p, dir) == Wall::Yes)
+            .collect();
+        maze.carve(closed[0].0, closed[0].1);
+        assert_eq!(maze.validate(), Ok(Layout::Loops(1)));
+    }
+
+    #[test]
+    fn open_boundary_is_reported() {
+        let mut maze = perfect();
+        maze.cells[5].set_active(false);
+        // the walls of a cell masked out after carving open onto nothing
+        let errors = maze.validate().unwrap_err();
+        assert!(!errors.is_empty());
+        assert!(errors.iter().all(|err| matches!(
+            err,
+            MazeError::OpenBoundary { .. } | MazeError::Unreachable { .. }
+        )));
+        assert!(errors
+            .iter()
+            .any(|err| matches!(err, MazeError::OpenBoundary { .. })));
+    }
+
+    #[test]
+    fn mismatched_wall_is_reported() {
+        // a crossing's passage opens the walls either side of it, so opening
+        // only one of them leaves the two sides of the tunnel disagreeing
+        let mut maze = Maze::new(3, 3);
+        let crossing = at(1, 1);
+        maze.cell_mut(crossing)
+            .set_under(Some(crate::position::Axis::Vertical));
+        maze.carve(crossing, Direction::Left);
+        maze.carve(crossing, Direction::Right);
+        maze.carve(at(0, 1), Direction::Down);
+        maze.set_wall(at(2, 1), Direction::Up, Wall::Yes);
+        let errors = maze.validate().unwrap_err();
+        assert!(
+            errors.contains(&MazeError::MismatchedWall {
+                pos: at(0, 1),
+                dir: Direction::Down
+            }),
+            "{:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn unreachable_is_reported() {
+        let mut maze = Maze::new(2, 2);
+        maze.carve(at(0, 0), Direction::Right);
+        maze.carve(at(0, 0), Direction::Down);
+        assert_eq!(
+            maze.validate(),
+            Err(vec![MazeError::Unreachable { pos: at(1, 1) }])
+        );
+    }
+
+    #[test]
+    fn bad_ends_are_reported() {
+        let mut maze = perfect();
+        maze.start = at(9, 9);
+        let goal = maze.goal;
+        maze.cell_mut(goal).set_active(false);
+        let errors = maze.validate().unwrap_err();
+        assert_eq!(
+            errors[..2],
+            [
+                MazeError::BadEnd {
+                    end: End::Start,
+                    pos: at(9, 9)
+                },
+                MazeError::BadEnd {
+                    end: End::Goal,
+                    pos: maze.goal
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn maze_without_cells_has_no_start() {
+        let mut maze = Maze::new(2, 2);
+        for cell in maze.cells.iter_mut() {
+            cell.set_active(false);
+        }
+        assert!(matches!(
+            maze.validate().unwrap_err()[..],
+            [
+                MazeError::BadEnd {
+                    end: End::Start,
+                    ..
+                },
+                MazeError::BadEnd { end: End::Goal, .. }
+            ]
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+use crate::position::Direction;
+
+/// the open walls of a Maze, one bit per wall between two cells. Each wall
+/// is kept by just one of the cells either side of it, on one of the sides
+/// `Topology::owned` lists, so the two sides of a wall can't disagree.
+/// Walls are up until they're opened, and a side no cell has opened a wall
+/// on takes up no memory
+#[derive(Clone, Debug, Default)]
+pub struct Walls {
+    /// a bitset over the cell indices for each direction
+    open: [Vec<u64>; Direction::COUNT],
+}
+
+impl Walls {
+    /// whether the wall on side `dir` of cell `i` is open
+    pub fn is_open(&self, dir: Direction, i: usize) -> bool {
+        self.open[dir as usize]
+            .get(i / 64)
+            .is_some_and(|word| word >> (i % 64) & 1 == 1)
+    }
+
+    /// open or close the wall on side `dir` of cell `i`, out of `len` cells.
+    /// The side's bitset takes room for all of them the first time it's used
+    pub fn set_open(&mut self, dir: Direction, i: usize, open: bool, len: usize) {
+        let bits = &mut self.open[dir as usize];
+        if bits.len() <= i / 64 {
+            if !open {
+                return;
+            }
+            bits.resize(usize::max(len, i + 1).div_ceil(64), 0);
+        }
+        if open {
+            bits[i / 64] |= 1 << (i % 64);
+        } else {
+            bits[i / 64] &= !(1 << (i % 64));
+        }
+    }
+}
+
+/// walls are the same when the same ones are open, however much room their
+/// bitsets have taken. A side that's never been opened has no words
+impl PartialEq for Walls {
+    fn eq(&self, other: &Self) -> bool {
+        self.open.iter().zip(&other.open).all(|(a, b)| {
+            let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+            long[..short.len()] == short[..] && long[short.len()..].iter().all(|&word| word == 0)
+        })
+    }
+}
@@ -1,13 +1,78 @@
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     Left,
     Right,
     Up,
     Down,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    /// up a staircase to the floor above
+    Above,
+    /// down a staircase to the floor below
+    Below,
+}
+
+/// the two straight lines through a square cell
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Axis {
+    /// Left to Right
+    Horizontal,
+    /// Up to Down
+    Vertical,
+}
+
+impl Direction {
+    /// number of directions, for tables indexed by `dir as usize`
+    pub const COUNT: usize = 10;
+
+    /// every direction, in the order of `dir as usize`
+    pub const ALL: [Direction; Direction::COUNT] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+        Direction::Above,
+        Direction::Below,
+    ];
+
+    /// the axis a square cell is crossed along going in this direction
+    pub fn axis(self) -> Option<Axis> {
+        match self {
+            Direction::Left | Direction::Right => Some(Axis::Horizontal),
+            Direction::Up | Direction::Down => Some(Axis::Vertical),
+            _ => None,
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::DownRight => Direction::UpLeft,
+            Direction::Above => Direction::Below,
+            Direction::Below => Direction::Above,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
+    /// floor, counted from the bottom
+    pub f: usize,
     pub r: usize,
     pub c: usize,
 }
@@ -15,22 +80,20 @@ pub struct Position {
 impl Position {
     pub fn apply_move(self, dir: Direction) -> Option<Position> {
         match dir {
-            Direction::Up => match self.r.checked_sub(1) {
-                Some(r) => Some(Self { r: r, c: self.c }),
-                None => None,
-            },
-            Direction::Down => match self.r.checked_add(1) {
-                Some(r) => Some(Self { r: r, c: self.c }),
-                None => None,
-            },
-            Direction::Left => match self.c.checked_sub(1) {
-                Some(c) => Some(Self { r: self.r, c: c }),
-                None => None,
-            },
-            Direction::Right => match self.c.checked_add(1) {
-                Some(c) => Some(Self { r: self.r, c: c }),
-                None => None,
-            },
+            Direction::Up => self.r.checked_sub(1).map(|r| Self { r, ..self }),
+            Direction::Down => self.r.checked_add(1).map(|r| Self { r, ..self }),
+            Direction::Left => self.c.checked_sub(1).map(|c| Self { c, ..self }),
+            Direction::Right => self.c.checked_add(1).map(|c| Self { c, ..self }),
+            Direction::Above => self.f.checked_add(1).map(|f| Self { f, ..self }),
+            Direction::Below => self.f.checked_sub(1).map(|f| Self { f, ..self }),
+            Direction::UpLeft => self.apply_move(Direction::Up)?.apply_move(Direction::Left),
+            Direction::UpRight => self.apply_move(Direction::Up)?.apply_move(Direction::Right),
+            Direction::DownLeft => self
+                .apply_move(Direction::Down)?
+                .apply_move(Direction::Left),
+            Direction::DownRight => self
+                .apply_move(Direction::Down)?
+                .apply_move(Direction::Right),
         }
     }
 }
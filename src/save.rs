@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::game::Game;
+use crate::maze::validate::MazeError;
+use crate::maze::Maze;
+use crate::topology::Topology;
+
+/// version of the save format `Save::to_json` writes, bumped whenever the
+/// JSON changes shape. `schema/save-v1.json` describes version 1
+pub const VERSION: u32 = 1;
+
+/// a Maze as it's being played, along with the Game it's a level of when
+/// there is one, in a form that can be written out and read back in
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Save {
+    /// version of the format the save was written in
+    pub version: u32,
+    pub game: Option<Game>,
+    pub maze: Maze,
+}
+
+/// seeds written as strings of their decimal digits, as JSON numbers
+/// past 2^53 don't survive being read by everything that reads JSON
+pub(crate) mod seed {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(seed)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let digits = String::deserialize(deserializer)?;
+        digits.parse().map_err(de::Error::custom)
+    }
+}
+
+/// why a save couldn't be loaded
+#[derive(Debug)]
+pub enum LoadError {
+    /// not JSON in the shape of a save
+    Json(serde_json::Error),
+    /// written in a version of the format this build doesn't read
+    Version(u32),
+    /// the cells don't fill the grid, or the start or goal is off it
+    Shape,
+    /// the Maze's walls don't add up
+    Invalid(Vec<MazeError>),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Json(err) => write!(f, "not a save: {}", err),
+            LoadError::Version(version) => {
+                write!(
+                    f,
+                    "save is version {}, only {} can be read",
+                    version, VERSION
+                )
+            }
+            LoadError::Shape => write!(f, "the maze's cells don't fit its grid"),
+            LoadError::Invalid(errors) => {
+                write!(f, "the maze is broken in {} places", errors.len())?;
+                errors.iter().try_for_each(|err| write!(f, "\n  {}", err))
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// just the version of a save, read before the rest so a save in another
+/// version of the format says so rather than failing on whatever changed
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl Save {
+    pub fn new(game: Option<Game>, maze: Maze) -> Self {
+        Self {
+            version: VERSION,
+            game,
+            maze,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("saves are plain data")
+    }
+
+    /// read a save back in, checking its Maze holds together the way a
+    /// generated one does
+    pub fn from_json(json: &str) -> Result<Self, LoadError> {
+        let header: Header = serde_json::from_str(json).map_err(LoadError::Json)?;
+        if header.version != VERSION {
+            return Err(LoadError::Version(header.version));
+        }
+        let save: Save = serde_json::from_str(json).map_err(LoadError::Json)?;
+        let maze = &save.maze;
+        if !fills_grid(maze)
+            || !maze.is_position_valid(maze.start)
+            || !maze.is_position_valid(maze.goal)
+        {
+            return Err(LoadError::Shape);
+        }
+        maze.validate().map_err(LoadError::Invalid)?;
+        Ok(save)
+    }
+}
+
+/// whether the cells of a loaded Maze fill its grid exactly, checked
+/// without overflowing on sizes far past any the cells could fill
+fn fills_grid(maze: &Maze) -> bool {
+    let floor_len = match maze.topology {
+        Topology::Square | Topology::Hex => maze.m.checked_mul(maze.n),
+        // every ring has a cell, and no more rings than cells can be
+        // summed up without overflowing
+        Topology::Polar if maze.m > maze.cells.len() => None,
+        Topology::Polar => Some(maze.floor_len()),
+    };
+    floor_len.and_then(|len| len.checked_mul(maze.floors)) == Some(maze.cells.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_round_trip() {
+        for (topology, level) in [
+            (Topology::Square, 14),
+            (Topology::Hex, 8),
+            (Topology::Polar, 12),
+            (Topology::Square, 16),
+        ] {
+            let game = Game {
+                level,
+                ..Game::with_seed(9, topology)
+            };
+            let mut maze = game.get_maze();
+            maze.cell_mut(maze.start).set_clicked(true);
+            let save = Save::from_json(&Save::new(Some(game), maze.clone()).to_json()).unwrap();
+            assert_eq!(save.maze, maze);
+            assert_eq!(save.game.unwrap().level, level);
+        }
+    }
+
+    #[test]
+    fn oversized_grids_are_rejected() {
+        let mut maze = Maze::new(2, 2);
+        maze.clear_walls();
+        let json = Save::new(None, maze).to_json();
+        let mut huge: serde_json::Value = serde_json::from_str(&json).unwrap();
+        huge["maze"]["m"] = (1u64 << 32).into();
+        huge["maze"]["n"] = (1u64 << 32).into();
+        huge["maze"]["cells"] = serde_json::json!([]);
+        huge["maze"]["walls"] = serde_json::json!([]);
+        let result = Save::from_json(&huge.to_string());
+        assert!(matches!(result, Err(LoadError::Shape)), "{:?}", result);
+    }
+
+    #[test]
+    fn seeds_are_written_as_strings() {
+        let game = Game::with_seed(u64::MAX, Topology::Square);
+        let maze = game.get_maze();
+        let json = Save::new(Some(game), maze).to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["game"]["seed"], "18446744073709551615");
+        assert!(value["maze"]["seed"].is_string());
+        assert_eq!(Save::from_json(&json).unwrap().game.unwrap().seed, u64::MAX);
+    }
+
+    #[test]
+    fn walls_on_sides_no_cell_keeps_are_rejected() {
+        let maze = Game::with_seed(4, Topology::Square).get_maze();
+        let cells = maze.cells.len();
+        let json = Save::new(None, maze).to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["maze"]["walls"].as_array().unwrap().len(), cells);
+
+        // Right is kept by the cell on the other side, as its Left
+        let mut stray = value.clone();
+        stray["maze"]["walls"][0] = serde_json::json!(["Right"]);
+        let result = Save::from_json(&stray.to_string());
+        assert!(matches!(result, Err(LoadError::Json(_))), "{:?}", result);
+
+        let mut short = value;
+        short["maze"]["walls"].as_array_mut().unwrap().pop();
+        let result = Save::from_json(&short.to_string());
+        assert!(matches!(result, Err(LoadError::Json(_))), "{:?}", result);
+    }
+}
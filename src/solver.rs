@@ -0,0 +1,276 @@
+mod a_star;
+mod bfs;
+mod dead_end_filling;
+mod dijkstra;
+mod tremaux;
+mod wall_follower;
+
+pub use a_star::AStar;
+pub use bfs::{breadth_first, Bfs};
+pub use dead_end_filling::DeadEndFilling;
+pub use dijkstra::{cheapest, Dijkstra};
+pub use tremaux::Tremaux;
+pub use wall_follower::{Hand, WallFollower};
+
+use std::collections::HashSet;
+
+use crate::maze::Maze;
+use crate::position::Position;
+
+/// a way from start to goal, along with how much work it took to find
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    /// cells from start to goal, both included
+    pub path: Vec<Position>,
+    /// cells in the order the solver first explored them
+    pub visited: Vec<Position>,
+    /// cells the solver stepped into, counting every time it walked back
+    /// over one. Searches that don't walk count each cell they expand once
+    pub steps: usize,
+}
+
+impl Solution {
+    /// number of different cells the solver looked at
+    pub fn explored(&self) -> usize {
+        self.visited.len()
+    }
+
+    /// what walking the path costs, counting the terrain of every cell
+    /// stepped onto after the start
+    pub fn cost(&self, maze: &Maze) -> usize {
+        self.path
+            .iter()
+            .skip(1)
+            .map(|p| maze.cell(*p).terrain().cost())
+            .sum()
+    }
+}
+
+/// an algorithm that finds its way through a Maze
+pub trait MazeSolver {
+    /// None if the solver can't get from start to goal
+    fn solve(&self, maze: &Maze, start: Position, goal: Position) -> Option<Solution>;
+}
+
+/// names accepted by `from_name`
+pub const NAMES: [&str; 7] = [
+    "a-star",
+    "bfs",
+    "dead-end-filling",
+    "dijkstra",
+    "left-wall",
+    "right-wall",
+    "tremaux",
+];
+
+/// look up a solver by name
+pub fn from_name(name: &str) -> Option<Box<dyn MazeSolver>> {
+    match name {
+        "a-star" => Some(Box::new(AStar)),
+        "bfs" => Some(Box::new(Bfs)),
+        "dead-end-filling" => Some(Box::new(DeadEndFilling)),
+        "dijkstra" => Some(Box::new(Dijkstra)),
+        "left-wall" => Some(Box::new(WallFollower { hand: Hand::Left })),
+        "right-wall" => Some(Box::new(WallFollower { hand: Hand::Right })),
+        "tremaux" => Some(Box::new(Tremaux)),
+        _ => None,
+    }
+}
+
+/// the next `count` cells to click on a cheapest way to the goal. The way
+/// carries on from whichever cell of the clicked path joined to the start is
+/// nearest the goal, or from the start while the path doesn't touch it
+pub fn hint(maze: &Maze, count: usize) -> Vec<Position> {
+    let path: HashSet<Position> = maze
+        .positions()
+        .filter(|p| maze.cell(*p).clicked())
+        .collect();
+    let to_goal = maze.distances(maze.goal);
+    let from = if path.contains(&maze.start) {
+        maze.reachable(maze.start, &path)
+            .into_iter()
+            .filter_map(|p| to_goal[maze.index(p)].map(|d| (d, p)))
+            .min_by_key(|&(d, p)| (d, maze.index(p)))
+            .map_or(maze.start, |(_, p)| p)
+    } else {
+        maze.start
+    };
+    Dijkstra
+        .solve(maze, from, maze.goal)
+        .map(|solution| {
+            solution
+                .path
+                .into_iter()
+                .filter(|p| !maze.cell(*p).clicked())
+                .take(count)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// follow `parents` back from the goal, giving the path from whichever
+/// cell has no parent to the goal
+fn trace(maze: &Maze, parents: &[Option<Position>], goal: Position) -> Vec<Position> {
+    let mut path = vec![goal];
+    while let Some(p) = parents[maze.index(*path.last().unwrap())] {
+        path.push(p);
+    }
+    path.reverse();
+    path
+}
+
+/// a solver wandering through the Maze one cell at a time. Whenever it
+/// comes back to a cell on its path the loop it made is cut off, so the
+/// path never visits a cell twice
+struct Walk {
+    path: Vec<Position>,
+    /// where each cell sits on the path, indexed like `cells`
+    on_path: Vec<Option<usize>>,
+    seen: Vec<bool>,
+    visited: Vec<Position>,
+    steps: usize,
+}
+
+impl Walk {
+    fn new(maze: &Maze, start: Position) -> Self {
+        let mut walk = Walk {
+            path: vec![start],
+            on_path: vec![None; maze.cells.len()],
+            seen: vec![false; maze.cells.len()],
+            visited: vec![start],
+            steps: 0,
+        };
+        walk.on_path[maze.index(start)] = Some(0);
+        walk.seen[maze.index(start)] = true;
+        walk
+    }
+
+    fn step(&mut self, maze: &Maze, to: Position) {
+        self.steps += 1;
+        let i = maze.index(to);
+        if !self.seen[i] {
+            self.seen[i] = true;
+            self.visited.push(to);
+        }
+        match self.on_path[i] {
+            Some(k) => {
+                for p in self.path.drain(k + 1..) {
+                    self.on_path[maze.index(p)] = None;
+                }
+            }
+            None => {
+                self.on_path[i] = Some(self.path.len());
+                self.path.push(to);
+            }
+        }
+    }
+
+    fn finish(self) -> Solution {
+        Solution {
+            path: self.path,
+            visited: self.visited,
+            steps: self.steps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::generator::{Kruskal, MazeGenerator, MazeRng, Weave};
+    use crate::maze::Placement;
+    use crate::topology::Topology;
+
+    /// a perfect Maze of each kind the solvers have to find their way
+    /// through, carved from `seed`
+    fn mazes(seed: u64) -> Vec<(&'static str, Maze)> {
+        let mut rng = MazeRng::seed_from_u64(seed);
+        let mut carve = |mut maze: Maze, generator: &dyn MazeGenerator| {
+            generator.generate(&mut maze, &mut rng);
+            maze.place_ends(Placement::Farthest);
+            maze
+        };
+        vec![
+            ("square", carve(Maze::new(9, 11), &Kruskal)),
+            (
+                "hex",
+                carve(
+                    Maze {
+                        topology: Topology::Hex,
+                        ..Maze::new(9, 11)
+                    },
+                    &Kruskal,
+                ),
+            ),
+            ("polar", carve(Maze::polar(7), &Kruskal)),
+            ("woven", carve(Maze::new(9, 11), &Weave::default())),
+            (
+                "wrapped",
+                carve(
+                    Maze {
+                        wrap: true,
+                        ..Maze::new(8, 10)
+                    },
+                    &Kruskal,
+                ),
+            ),
+        ]
+    }
+
+    /// check `solution` walks from start to goal through open walls
+    /// without visiting a cell twice
+    fn assert_walks(maze: &Maze, solution: &Solution, context: &str) {
+        assert_eq!(solution.path.first(), Some(&maze.start), "{}", context);
+        assert_eq!(solution.path.last(), Some(&maze.goal), "{}", context);
+        let cells: HashSet<&Position> = solution.path.iter().collect();
+        assert_eq!(cells.len(), solution.path.len(), "{}", context);
+        for step in solution.path.windows(2) {
+            assert!(
+                maze.links(step[0]).iter().any(|&(_, q)| q == step[1]),
+                "{}: {:?} doesn't lead to {:?}",
+                context,
+                step[0],
+                step[1]
+            );
+        }
+    }
+
+    #[test]
+    fn every_solver_reaches_the_goal() {
+        for seed in 0..3 {
+            for (kind, maze) in mazes(seed) {
+                for name in NAMES {
+                    let context = format!("{} on {}, seed {}", name, kind, seed);
+                    let solution = from_name(name)
+                        .unwrap()
+                        .solve(&maze, maze.start, maze.goal)
+                        .unwrap_or_else(|| panic!("{} found no way", context));
+                    assert_walks(&maze, &solution, &context);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn searches_find_shortest_paths() {
+        // loops give the searches more than one way to the goal, and tunnels
+        // under crossings skip a cell, which A*'s estimate has to allow for
+        for seed in 0..6 {
+            for (kind, mut maze) in mazes(seed) {
+                maze.braid(0.5, &mut MazeRng::seed_from_u64(seed));
+                let shortest = maze.distances(maze.start)[maze.index(maze.goal)].unwrap() + 1;
+                for name in ["a-star", "bfs", "dijkstra"] {
+                    let context = format!("{} on {}, seed {}", name, kind, seed);
+                    let solution = from_name(name)
+                        .unwrap()
+                        .solve(&maze, maze.start, maze.goal)
+                        .unwrap();
+                    assert_walks(&maze, &solution, &context);
+                    assert_eq!(solution.path.len(), shortest, "{}", context);
+                }
+            }
+        }
+    }
+}
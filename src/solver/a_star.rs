@@ -0,0 +1,119 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::{trace, MazeSolver, Solution};
+use crate::maze::Maze;
+use crate::position::Position;
+use crate::topology::Topology;
+
+/// A*: always explore the cell whose path so far plus an estimate of the
+/// way left is shortest. The estimate never overshoots, so the path found
+/// is a shortest one
+pub struct AStar;
+
+impl MazeSolver for AStar {
+    fn solve(&self, maze: &Maze, start: Position, goal: Position) -> Option<Solution> {
+        let mut best = vec![usize::MAX; maze.cells.len()];
+        let mut parents = vec![None; maze.cells.len()];
+        let mut closed = vec![false; maze.cells.len()];
+        let mut visited = vec![];
+
+        // the heap orders by estimated total, then by estimate left so ties
+        // go to the cell nearer the goal, then by when the cell was queued
+        let mut queued = vec![start];
+        let mut heap = BinaryHeap::new();
+        best[maze.index(start)] = 0;
+        // a tunnel under a crossing moves two cells in one step
+        let reach = if maze.is_woven() { 2 } else { 1 };
+        let h = estimate(maze, start, goal, reach);
+        heap.push(Reverse((h, h, 0)));
+
+        while let Some(Reverse((_, _, k))) = heap.pop() {
+            let p = queued[k];
+            let i = maze.index(p);
+            if closed[i] {
+                continue;
+            }
+            closed[i] = true;
+            visited.push(p);
+            if p == goal {
+                return Some(Solution {
+                    path: trace(maze, &parents, goal),
+                    steps: visited.len(),
+                    visited,
+                });
+            }
+            for (_, new_pos) in maze.links(p) {
+                let j = maze.index(new_pos);
+                let g = best[i] + 1;
+                if g < best[j] {
+                    best[j] = g;
+                    parents[j] = Some(p);
+                    let h = estimate(maze, new_pos, goal, reach);
+                    heap.push(Reverse((g + h, h, queued.len())));
+                    queued.push(new_pos);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// fewest steps that could possibly lead from `a` to `b`. Every step moves
+/// at most one row and one column, or one floor, except that it moves up
+/// to `reach` rows or columns through tunnels
+fn estimate(maze: &Maze, a: Position, b: Position, reach: usize) -> usize {
+    let gap = |x: usize, y: usize, len: usize| {
+        let d = x.abs_diff(y);
+        if maze.wrap {
+            usize::min(d, len - d)
+        } else {
+            d
+        }
+    };
+    let rows = gap(a.r, b.r, maze.m);
+    let flat = match maze.topology {
+        Topology::Square => rows + gap(a.c, b.c, maze.n),
+        Topology::Hex => usize::max(rows, gap(a.c, b.c, maze.n)),
+        // columns are numbered differently on every ring
+        Topology::Polar => rows,
+    };
+    flat.div_ceil(reach) + a.f.abs_diff(b.f)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::generator::{MazeGenerator, MazeRng, Weave};
+
+    #[test]
+    fn estimate_never_overshoots_through_tunnels() {
+        let mut tunnel_shortcuts = 0;
+        for seed in 0..10 {
+            let mut rng = MazeRng::seed_from_u64(seed);
+            let mut maze = Maze::new(10, 10);
+            Weave { density: 0.6 }.generate(&mut maze, &mut rng);
+            maze.braid(1.0, &mut rng);
+            assert!(maze.is_woven());
+            for goal in maze.positions() {
+                let distances = maze.distances(goal);
+                for p in maze.positions() {
+                    let steps = distances[maze.index(p)].unwrap();
+                    assert!(
+                        estimate(&maze, p, goal, 2) <= steps,
+                        "{:?} to {:?}",
+                        p,
+                        goal
+                    );
+                    if estimate(&maze, p, goal, 1) > steps {
+                        tunnel_shortcuts += 1;
+                    }
+                }
+            }
+        }
+        // counting a tunnel as a single cell would have overshot
+        assert!(tunnel_shortcuts > 0);
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::VecDeque;
+
+use super::{trace, MazeSolver, Solution};
+use crate::maze::Maze;
+use crate::position::Position;
+
+/// breadth first search: explore the Maze in rings of growing distance
+/// from the start, which finds a shortest path
+pub struct Bfs;
+
+impl MazeSolver for Bfs {
+    fn solve(&self, maze: &Maze, start: Position, goal: Position) -> Option<Solution> {
+        breadth_first(maze, start, goal, |_| true)
+    }
+}
+
+/// shortest path from start to goal through cells `allowed` lets in. The
+/// start is always let in
+pub fn breadth_first(
+    maze: &Maze,
+    start: Position,
+    goal: Position,
+    allowed: impl Fn(Position) -> bool,
+) -> Option<Solution> {
+    let mut parents = vec![None; maze.cells.len()];
+    let mut seen = vec![false; maze.cells.len()];
+    let mut visited = vec![];
+    seen[maze.index(start)] = true;
+    let mut queue = VecDeque::from([start]);
+    while let Some(p) = queue.pop_front() {
+        visited.push(p);
+        if p == goal {
+            return Some(Solution {
+                path: trace(maze, &parents, goal),
+                steps: visited.len(),
+                visited,
+            });
+        }
+        for (_, new_pos) in maze.links(p) {
+            let i = maze.index(new_pos);
+            if !seen[i] && allowed(new_pos) {
+                seen[i] = true;
+                parents[i] = Some(p);
+                queue.push_back(new_pos);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,49 @@
+use std::collections::VecDeque;
+
+use super::{breadth_first, MazeSolver, Solution};
+use crate::maze::Maze;
+use crate::position::Position;
+
+/// dead-end filling: fill in every dead end other than the start and goal,
+/// then every cell that became a dead end because of it. What's left over
+/// is the solution, plus any loops in a braided Maze
+pub struct DeadEndFilling;
+
+impl MazeSolver for DeadEndFilling {
+    fn solve(&self, maze: &Maze, start: Position, goal: Position) -> Option<Solution> {
+        let mut open = vec![0; maze.cells.len()];
+        let mut filled = vec![false; maze.cells.len()];
+        let mut queue = VecDeque::new();
+        for p in maze.positions() {
+            open[maze.index(p)] = maze.links(p).len();
+            if open[maze.index(p)] <= 1 && p != start && p != goal {
+                queue.push_back(p);
+            }
+        }
+
+        let mut visited = vec![];
+        while let Some(p) = queue.pop_front() {
+            filled[maze.index(p)] = true;
+            visited.push(p);
+            for (_, new_pos) in maze.links(p) {
+                let i = maze.index(new_pos);
+                if filled[i] {
+                    continue;
+                }
+                open[i] -= 1;
+                if open[i] == 1 && new_pos != start && new_pos != goal {
+                    queue.push_back(new_pos);
+                }
+            }
+        }
+
+        // walk whatever is left, which in a perfect Maze is just the path
+        let rest = breadth_first(maze, start, goal, |p| !filled[maze.index(p)])?;
+        visited.extend(rest.visited);
+        Some(Solution {
+            path: rest.path,
+            steps: visited.len(),
+            visited,
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::{trace, MazeSolver, Solution};
+use crate::maze::Maze;
+use crate::position::Position;
+
+/// Dijkstra's algorithm: always explore the cell that's cheapest to reach
+/// so far, counting what the terrain of each cell stepped onto costs. The
+/// path found is a cheapest one rather than the one with fewest cells
+pub struct Dijkstra;
+
+impl MazeSolver for Dijkstra {
+    fn solve(&self, maze: &Maze, start: Position, goal: Position) -> Option<Solution> {
+        cheapest(maze, start, goal, |_| true)
+    }
+}
+
+/// cheapest path from start to goal through cells `allowed` lets in. The
+/// start is always let in
+pub fn cheapest(
+    maze: &Maze,
+    start: Position,
+    goal: Position,
+    allowed: impl Fn(Position) -> bool,
+) -> Option<Solution> {
+    let mut best = vec![usize::MAX; maze.cells.len()];
+    let mut parents = vec![None; maze.cells.len()];
+    let mut closed = vec![false; maze.cells.len()];
+    let mut visited = vec![];
+
+    // the heap orders by cost, then by when the cell was queued
+    let mut queued = vec![start];
+    let mut heap = BinaryHeap::from([Reverse((0, 0))]);
+    best[maze.index(start)] = 0;
+
+    while let Some(Reverse((cost, k))) = heap.pop() {
+        let p = queued[k];
+        let i = maze.index(p);
+        if closed[i] {
+            continue;
+        }
+        closed[i] = true;
+        visited.push(p);
+        if p == goal {
+            return Some(Solution {
+                path: trace(maze, &parents, goal),
+                steps: visited.len(),
+                visited,
+            });
+        }
+        for (_, new_pos) in maze.links(p) {
+            let j = maze.index(new_pos);
+            let g = cost + maze.cell(new_pos).terrain().cost();
+            if g < best[j] && allowed(new_pos) {
+                best[j] = g;
+                parents[j] = Some(p);
+                heap.push(Reverse((g, queued.len())));
+                queued.push(new_pos);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::generator::{GrowingTree, MazeGenerator, MazeRng};
+    use crate::solver::Bfs;
+
+    /// cost of the cheapest way to every cell, relaxing every link until
+    /// nothing gets cheaper
+    fn cheapest_costs(maze: &Maze, start: Position) -> Vec<usize> {
+        let mut best = vec![usize::MAX; maze.cells.len()];
+        best[maze.index(start)] = 0;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in maze.positions() {
+                let cost = best[maze.index(p)];
+                if cost == usize::MAX {
+                    continue;
+                }
+                for (_, q) in maze.links(p) {
+                    let g = cost + maze.cell(q).terrain().cost();
+                    if g < best[maze.index(q)] {
+                        best[maze.index(q)] = g;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn cheapest_paths_beat_shortest_ones_on_terrain() {
+        let mut cheaper = 0;
+        for seed in 0..20 {
+            let rng = &mut MazeRng::seed_from_u64(seed);
+            let mut maze = Maze::new(12, 12);
+            GrowingTree::default().generate(&mut maze, rng);
+            maze.braid(1.0, rng);
+            maze.scatter_terrain(0.3, rng);
+
+            let dijkstra = Dijkstra.solve(&maze, maze.start, maze.goal).unwrap();
+            let bfs = Bfs.solve(&maze, maze.start, maze.goal).unwrap();
+            let best = cheapest_costs(&maze, maze.start)[maze.index(maze.goal)];
+            assert_eq!(dijkstra.cost(&maze), best, "seed {}", seed);
+            assert!(dijkstra.cost(&maze) <= bfs.cost(&maze), "seed {}", seed);
+            if dijkstra.cost(&maze) < bfs.cost(&maze) {
+                cheaper += 1;
+            }
+        }
+        assert!(cheaper > 0, "terrain never changed the best path");
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use super::{MazeSolver, Solution, Walk};
+use crate::maze::Maze;
+use crate::position::Position;
+
+/// Trémaux: mark every passage each time it's walked and never walk one a
+/// third time. Coming back into a cell seen before down a fresh passage
+/// means a loop, so turn straight back. Otherwise take the least marked
+/// way on. This finds the goal in any Maze, loops and all
+pub struct Tremaux;
+
+impl MazeSolver for Tremaux {
+    fn solve(&self, maze: &Maze, start: Position, goal: Position) -> Option<Solution> {
+        // passages named by the cells at either end, first one first
+        let passage = |a: Position, b: Position| {
+            let (i, j) = (maze.index(a), maze.index(b));
+            (usize::min(i, j), usize::max(i, j))
+        };
+        let mut marks: HashMap<(usize, usize), u8> = HashMap::new();
+        let mut walk = Walk::new(maze, start);
+        let (mut p, mut from) = (start, None);
+        while p != goal {
+            let exits: Vec<Position> = maze.links(p).into_iter().map(|(_, q)| q).collect();
+            let marked = |q: Position| marks.get(&passage(p, q)).copied().unwrap_or(0);
+            let seen_before = exits.iter().any(|&q| Some(q) != from && marked(q) > 0);
+            let next = match from {
+                Some(q) if seen_before && marked(q) == 1 => Some(q),
+                _ => exits
+                    .iter()
+                    .copied()
+                    .filter(|&q| marked(q) < 2)
+                    .min_by_key(|&q| marked(q)),
+            }?;
+            *marks.entry(passage(p, next)).or_default() += 1;
+            walk.step(maze, next);
+            (p, from) = (next, Some(p));
+        }
+        Some(walk.finish())
+    }
+}
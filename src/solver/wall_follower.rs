@@ -0,0 +1,48 @@
+use super::{MazeSolver, Solution, Walk};
+use crate::maze::Maze;
+use crate::position::{Direction, Position};
+
+/// the hand kept on the wall
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// wall follower: keep one hand on the wall and walk until the goal turns
+/// up. That always works in a perfect Maze, but can circle forever around
+/// a loop in a braided one, in which case it gives up
+pub struct WallFollower {
+    pub hand: Hand,
+}
+
+impl MazeSolver for WallFollower {
+    fn solve(&self, maze: &Maze, start: Position, goal: Position) -> Option<Solution> {
+        // walking around a tree goes down every passage twice, so a walk
+        // longer than that has to be going round in circles
+        let limit = 2 * Direction::COUNT * maze.cells.len();
+        let mut walk = Walk::new(maze, start);
+        let (mut p, mut from) = (start, None);
+        while p != goal {
+            // exits come in turning order, clockwise on square and hex
+            // cells, with the stairs last
+            let links = maze.links(p);
+            if links.is_empty() || walk.steps > limit {
+                return None;
+            }
+            let k = links.len();
+            let next = match from.and_then(|q| links.iter().position(|(_, l)| *l == q)) {
+                // the first exit turning away from the way back
+                Some(back) => match self.hand {
+                    Hand::Left => (back + 1) % k,
+                    Hand::Right => (back + k - 1) % k,
+                },
+                None => 0,
+            };
+            let new_pos = links[next].1;
+            walk.step(maze, new_pos);
+            (p, from) = (new_pos, Some(p));
+        }
+        Some(walk.finish())
+    }
+}
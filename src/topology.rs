@@ -0,0 +1,252 @@
+use std::f64::consts::PI;
+use std::sync::OnceLock;
+
+use crate::position::{Direction, Position};
+
+/// how the cells of a Maze are laid out and which cells neighbor each other
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    /// square cells with four walls
+    Square,
+    /// pointy-topped hexagons with six walls, odd rows shifted half a
+    /// cell to the right of even rows
+    Hex,
+    /// concentric rings around a single centre cell, row `r` being ring
+    /// `r`. Up leads inward, Down outward, Right clockwise. Where a ring
+    /// splits its cells in two, DownLeft and DownRight take the place of Down
+    Polar,
+}
+
+/// runs of rings kept track of, enough for hundreds of millions of rings
+const RUNS: usize = 32;
+
+/// number of cells in ring `ring` of a polar grid. Each ring keeps its
+/// cells about as wide as they are deep by splitting every cell of the
+/// ring inside it in two once they get too wide
+pub fn ring_len(ring: usize) -> usize {
+    1 << (splits().partition_point(|&first| first <= ring) - 1)
+}
+
+/// first ring of each run of rings of a polar grid that have the same
+/// number of cells, run `k` having 2^k cells to a ring. Worked out once as
+/// every cell lookup needs them
+fn splits() -> &'static [usize] {
+    static SPLITS: OnceLock<Vec<usize>> = OnceLock::new();
+    SPLITS.get_or_init(|| {
+        let mut splits = vec![0];
+        for k in 0..RUNS - 1 {
+            splits.push(next_split(splits[k] + 1, 1 << k));
+        }
+        splits
+    })
+}
+
+/// first ring from `from` on that splits the cells of a ring of `len`
+/// cells, which is where they get more than one and a half cells wide
+fn next_split(from: usize, len: usize) -> usize {
+    let width = |r: usize| (2.0 * PI * r as f64 / len as f64).round() as usize;
+    // start from the ring worked out directly and fix up any rounding
+    let mut r = usize::max(from, (0.75 * len as f64 / PI) as usize);
+    while r > from && width(r - 1) >= 2 {
+        r -= 1;
+    }
+    while width(r) < 2 {
+        r += 1;
+    }
+    r
+}
+
+impl Topology {
+    /// directions a cell can have neighbors in, clockwise from the top
+    pub fn directions(self) -> &'static [Direction] {
+        match self {
+            Topology::Square => &[
+                Direction::Up,
+                Direction::Right,
+                Direction::Down,
+                Direction::Left,
+            ],
+            Topology::Hex => &[
+                Direction::UpRight,
+                Direction::Right,
+                Direction::DownRight,
+                Direction::DownLeft,
+                Direction::Left,
+                Direction::UpLeft,
+            ],
+            Topology::Polar => &[
+                Direction::Up,
+                Direction::Right,
+                Direction::DownRight,
+                Direction::Down,
+                Direction::DownLeft,
+                Direction::Left,
+            ],
+        }
+    }
+
+    /// directions whose walls a cell keeps, the sides facing back toward
+    /// the first cell along with the stairs down. Its other walls are kept
+    /// by the neighbors behind them
+    pub fn owned(self) -> &'static [Direction] {
+        match self {
+            Topology::Square | Topology::Polar => {
+                &[Direction::Left, Direction::Up, Direction::Below]
+            }
+            Topology::Hex => &[
+                Direction::Left,
+                Direction::UpLeft,
+                Direction::UpRight,
+                Direction::Below,
+            ],
+        }
+    }
+
+    /// number of cells in row `r` of a grid whose widest row has `n` cells
+    pub fn row_len(self, r: usize, n: usize) -> usize {
+        match self {
+            Topology::Square | Topology::Hex => n,
+            Topology::Polar => ring_len(r),
+        }
+    }
+
+    /// index of the first cell of row `r` in a grid stored row by row
+    pub fn row_start(self, r: usize, n: usize) -> usize {
+        match self {
+            Topology::Square | Topology::Hex => r * n,
+            Topology::Polar => {
+                let splits = splits();
+                (0..RUNS)
+                    .take_while(|&k| splits[k] < r)
+                    .map(|k| {
+                        let end = splits.get(k + 1).map_or(r, |&next| usize::min(next, r));
+                        (end - splits[k]) << k
+                    })
+                    .sum()
+            }
+        }
+    }
+
+    /// the row holding index `i` of a grid stored row by row, whose widest
+    /// row has `n` cells
+    pub fn row_of(self, i: usize, n: usize) -> usize {
+        match self {
+            Topology::Square | Topology::Hex => i / n,
+            Topology::Polar => {
+                let splits = splits();
+                let mut start = 0;
+                for k in 0..RUNS {
+                    let rings = splits
+                        .get(k + 1)
+                        .map_or(usize::MAX, |&next| next - splits[k]);
+                    if i - start < rings.saturating_mul(1 << k) {
+                        return splits[k] + ((i - start) >> k);
+                    }
+                    start += rings << k;
+                }
+                unreachable!()
+            }
+        }
+    }
+
+    /// the position one step from `p` in `dir`, without checking the far
+    /// side of the grid or the top floor
+    pub fn step(self, p: Position, dir: Direction) -> Option<Position> {
+        // stairs lead straight between floors whatever the grid
+        if let Direction::Above | Direction::Below = dir {
+            return p.apply_move(dir);
+        }
+        match self {
+            Topology::Square => match dir {
+                Direction::Up | Direction::Down | Direction::Left | Direction::Right => {
+                    p.apply_move(dir)
+                }
+                _ => None,
+            },
+            Topology::Hex => {
+                // diagonals land either straight above/below or one column
+                // over depending on which way the row is shifted
+                let shifted = p.r % 2 == 1;
+                let dir = match (dir, shifted) {
+                    (Direction::UpLeft, true) | (Direction::UpRight, false) => Direction::Up,
+                    (Direction::DownLeft, true) | (Direction::DownRight, false) => Direction::Down,
+                    (Direction::Up | Direction::Down, _) => return None,
+                    _ => dir,
+                };
+                p.apply_move(dir)
+            }
+            Topology::Polar => {
+                let len = ring_len(p.r);
+                let split = |r| ring_len(r + 1) / ring_len(r);
+                match dir {
+                    Direction::Up if p.r > 0 => Some(Position {
+                        r: p.r - 1,
+                        c: p.c / split(p.r - 1),
+                        ..p
+                    }),
+                    // the centre cell has no ring to go around
+                    Direction::Left if len > 1 => Some(Position {
+                        c: (p.c + len - 1) % len,
+                        ..p
+                    }),
+                    Direction::Right if len > 1 => Some(Position {
+                        c: (p.c + 1) % len,
+                        ..p
+                    }),
+                    Direction::Down if split(p.r) == 1 => Some(Position { r: p.r + 1, ..p }),
+                    Direction::DownLeft if split(p.r) == 2 => Some(Position {
+                        r: p.r + 1,
+                        c: 2 * p.c,
+                        ..p
+                    }),
+                    Direction::DownRight if split(p.r) == 2 => Some(Position {
+                        r: p.r + 1,
+                        c: 2 * p.c + 1,
+                        ..p
+                    }),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// the direction leading back to `p` from its neighbor in `dir`
+    pub fn back(self, p: Position, dir: Direction) -> Direction {
+        match (self, dir) {
+            (Topology::Polar, Direction::Up) if ring_len(p.r) > ring_len(p.r - 1) => {
+                if p.c.is_multiple_of(2) {
+                    Direction::DownLeft
+                } else {
+                    Direction::DownRight
+                }
+            }
+            (Topology::Polar, Direction::DownLeft | Direction::DownRight) => Direction::Up,
+            _ => dir.opposite(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_table_matches_ring_by_ring_sizes() {
+        // every ring worked out from the one inside it, the way the table
+        // is meant to agree with
+        let mut len = 1;
+        let mut start = 0;
+        for r in 0..5000 {
+            if r > 0 {
+                let width = 2.0 * PI * r as f64 / len as f64;
+                len *= (width.round() as usize).clamp(1, 2);
+            }
+            assert_eq!(ring_len(r), len, "ring {}", r);
+            assert_eq!(Topology::Polar.row_start(r, 0), start, "ring {}", r);
+            assert_eq!(Topology::Polar.row_of(start, 0), r, "ring {}", r);
+            assert_eq!(Topology::Polar.row_of(start + len - 1, 0), r, "ring {}", r);
+            start += len;
+        }
+    }
+}